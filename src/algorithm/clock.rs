@@ -0,0 +1,91 @@
+use std::collections::BTreeSet;
+
+use super::ReplacementPolicy;
+use crate::process::ProcessPage;
+
+/// Política del reloj (segunda oportunidad): una manecilla recorre los marcos de la memoria real
+/// en orden circular. Si la página bajo la manecilla tiene prendido su bit de referencia se le
+/// apaga y se le da una segunda oportunidad, si no, esa página es la que se reemplaza.
+/// Guarda el índice del marco al que apunta la manecilla
+#[derive(Debug, Default)]
+pub struct ClockPolicy {
+    hand: usize,
+}
+
+impl ClockPolicy {
+    /// Constructor de la política con la manecilla en el primer marco
+    pub fn new() -> Self {
+        ClockPolicy::default()
+    }
+
+    /// Avanza la manecilla hasta encontrar una página sin bit de referencia en un marco que sea
+    /// candidato, apagando los bits de referencia de los candidatos que encuentre en el camino
+    /// (los bits de los demás marcos no se tocan).
+    /// Regresa el índice del marco y deja la manecilla en el siguiente
+    fn advance(
+        &mut self,
+        real_memory: &mut [Option<ProcessPage>],
+        is_candidate: impl Fn(usize) -> bool,
+    ) -> usize {
+        // En dos vueltas completas siempre se encuentra una página, porque en la primera se
+        // apagan todos los bits de referencia de los candidatos
+        for _ in 0..2 * real_memory.len() {
+            let frame_index = self.hand;
+            self.hand = (self.hand + 1) % real_memory.len();
+            if !is_candidate(frame_index) {
+                continue;
+            }
+            if let Some(page) = real_memory[frame_index].as_mut() {
+                if page.is_referenced() {
+                    page.set_referenced(false);
+                } else {
+                    return frame_index;
+                }
+            }
+        }
+        // El sistema sólo pide reemplazar una página cuando hay por lo menos un candidato
+        // ocupado, y en la primera vuelta la manecilla apaga su bit, por lo que la segunda vuelta
+        // siempre lo encuentra
+        unreachable!(
+            "La manecilla del reloj dio dos vueltas sin encontrar un marco candidato ocupado"
+        );
+    }
+}
+
+impl ReplacementPolicy for ClockPolicy {
+    fn reset(&mut self) {
+        self.hand = 0;
+    }
+
+    /// Regresa el índice del primer marco sin bit de referencia a partir de la manecilla
+    fn find_page_to_replace(&mut self, real_memory: &mut [Option<ProcessPage>]) -> usize {
+        self.advance(real_memory, |_| true)
+    }
+
+    /// Regresa el índice del primer marco candidato sin bit de referencia a partir de la
+    /// manecilla
+    fn find_page_to_replace_among(
+        &mut self,
+        real_memory: &mut [Option<ProcessPage>],
+        candidates: &BTreeSet<usize>,
+    ) -> usize {
+        self.advance(real_memory, |frame_index| candidates.contains(&frame_index))
+    }
+
+    /// Regresa un set con los índices de los primeros `n` marcos sin bit de referencia a partir
+    /// de la manecilla
+    fn find_n_pages_to_replace(
+        &mut self,
+        real_memory: &mut [Option<ProcessPage>],
+        n: usize,
+    ) -> BTreeSet<usize> {
+        let mut page_indexes = BTreeSet::new();
+        for _ in 0..n {
+            let frame_index = self.advance(real_memory, |frame_index| {
+                !page_indexes.contains(&frame_index)
+            });
+            page_indexes.insert(frame_index);
+        }
+        page_indexes
+    }
+}
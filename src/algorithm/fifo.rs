@@ -0,0 +1,69 @@
+use std::collections::BTreeSet;
+
+use super::frame_list::FrameList;
+use super::ReplacementPolicy;
+use crate::process::ProcessPage;
+
+/// Política First In First Out: reemplaza la página que lleva más tiempo en la memoria real,
+/// contando desde la última vez que se cargó (una página que regresa del espacio swap vuelve a
+/// formarse al final, como en la definición clásica de FIFO)
+/// Guarda los marcos ocupados en una lista ligada ordenada de la carga más antigua a la más
+/// reciente: cada vez que se carga una página su marco se forma al final de la lista, por lo que
+/// las páginas por reemplazar siempre están al inicio
+#[derive(Debug, Default)]
+pub struct FifoPolicy {
+    frames: FrameList,
+}
+
+impl FifoPolicy {
+    /// Constructor de la política sin marcos ocupados
+    pub fn new() -> Self {
+        FifoPolicy::default()
+    }
+}
+
+impl ReplacementPolicy for FifoPolicy {
+    fn on_load(&mut self, frame: usize, _page: &ProcessPage) {
+        self.frames.push_back(frame);
+    }
+
+    fn reset(&mut self) {
+        *self = FifoPolicy::default();
+    }
+
+    fn on_evict(&mut self, frame: usize, _page: &ProcessPage) {
+        self.frames.remove(frame);
+    }
+
+    fn on_free(&mut self, frame: usize, _page: &ProcessPage) {
+        self.frames.remove(frame);
+    }
+
+    /// Regresa el índice del marco al que se debería reemplazar dependiendo de la carga de la
+    /// página, que es el primero de la lista
+    fn find_page_to_replace(&mut self, _real_memory: &mut [Option<ProcessPage>]) -> usize {
+        self.frames.front().unwrap()
+    }
+
+    /// Regresa el índice del primer marco de la lista que es candidato
+    fn find_page_to_replace_among(
+        &mut self,
+        _real_memory: &mut [Option<ProcessPage>],
+        candidates: &BTreeSet<usize>,
+    ) -> usize {
+        self.frames
+            .iter()
+            .find(|frame| candidates.contains(frame))
+            .unwrap()
+    }
+
+    /// Regresa un set de índices de marcos que se deberían reemplazar dependiendo de la carga
+    /// de cada página, que son los primeros `n` de la lista
+    fn find_n_pages_to_replace(
+        &mut self,
+        _real_memory: &mut [Option<ProcessPage>],
+        n: usize,
+    ) -> BTreeSet<usize> {
+        self.frames.iter().take(n).collect()
+    }
+}
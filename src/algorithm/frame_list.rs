@@ -0,0 +1,71 @@
+/// Lista doblemente ligada intrusiva de índices de marcos: en vez de guardar nodos, guarda para
+/// cada índice de marco el marco anterior y el siguiente, por lo que insertar al final, mover al
+/// final y quitar un marco toman tiempo constante
+/// Guarda:
+/// - prev: para cada marco, el marco anterior en la lista
+/// - next: para cada marco, el marco siguiente en la lista
+/// - linked: para cada marco, si se encuentra en la lista
+/// - head: el primer marco de la lista
+/// - tail: el último marco de la lista
+#[derive(Debug, Default)]
+pub struct FrameList {
+    prev: Vec<Option<usize>>,
+    next: Vec<Option<usize>>,
+    linked: Vec<bool>,
+    head: Option<usize>,
+    tail: Option<usize>,
+}
+
+impl FrameList {
+    /// Agranda los vectores para que el índice de marco proporcionado sea válido
+    fn ensure_capacity(&mut self, frame: usize) {
+        if frame >= self.linked.len() {
+            self.prev.resize(frame + 1, None);
+            self.next.resize(frame + 1, None);
+            self.linked.resize(frame + 1, false);
+        }
+    }
+
+    /// Añade el marco al final de la lista. Si ya estaba en la lista lo mueve al final
+    pub fn push_back(&mut self, frame: usize) {
+        self.remove(frame);
+        self.ensure_capacity(frame);
+        self.prev[frame] = self.tail;
+        self.next[frame] = None;
+        match self.tail {
+            Some(tail) => self.next[tail] = Some(frame),
+            None => self.head = Some(frame),
+        }
+        self.tail = Some(frame);
+        self.linked[frame] = true;
+    }
+
+    /// Quita el marco de la lista si es que estaba en ella
+    pub fn remove(&mut self, frame: usize) {
+        if !self.linked.get(frame).copied().unwrap_or(false) {
+            return;
+        }
+        // Conectamos el marco anterior con el siguiente
+        match self.prev[frame] {
+            Some(prev) => self.next[prev] = self.next[frame],
+            None => self.head = self.next[frame],
+        }
+        match self.next[frame] {
+            Some(next) => self.prev[next] = self.prev[frame],
+            None => self.tail = self.prev[frame],
+        }
+        self.prev[frame] = None;
+        self.next[frame] = None;
+        self.linked[frame] = false;
+    }
+
+    /// Regresa el primer marco de la lista
+    pub fn front(&self) -> Option<usize> {
+        self.head
+    }
+
+    /// Regresa un iterador por los marcos de la lista, del primero al último
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(self.head, move |&frame| self.next[frame])
+    }
+}
@@ -0,0 +1,71 @@
+use std::collections::BTreeSet;
+
+use super::frame_list::FrameList;
+use super::ReplacementPolicy;
+use crate::process::ProcessPage;
+
+/// Política Least Recently Used: reemplaza la página con el tiempo de acceso más antiguo
+/// Guarda los marcos ocupados en una lista ligada ordenada del acceso más antiguo al más reciente:
+/// cada vez que se carga o se accede a una página su marco se mueve al final de la lista, por lo
+/// que las páginas por reemplazar siempre están al inicio
+#[derive(Debug, Default)]
+pub struct LruPolicy {
+    frames: FrameList,
+}
+
+impl LruPolicy {
+    /// Constructor de la política sin marcos ocupados
+    pub fn new() -> Self {
+        LruPolicy::default()
+    }
+}
+
+impl ReplacementPolicy for LruPolicy {
+    fn on_load(&mut self, frame: usize, _page: &ProcessPage) {
+        self.frames.push_back(frame);
+    }
+
+    fn on_access(&mut self, frame: usize, _page: &ProcessPage) {
+        self.frames.push_back(frame);
+    }
+
+    fn reset(&mut self) {
+        *self = LruPolicy::default();
+    }
+
+    fn on_evict(&mut self, frame: usize, _page: &ProcessPage) {
+        self.frames.remove(frame);
+    }
+
+    fn on_free(&mut self, frame: usize, _page: &ProcessPage) {
+        self.frames.remove(frame);
+    }
+
+    /// Regresa el índice del marco al que se debería reemplazar dependiendo del tiempo de acceso
+    /// de la página, que es el primero de la lista
+    fn find_page_to_replace(&mut self, _real_memory: &mut [Option<ProcessPage>]) -> usize {
+        self.frames.front().unwrap()
+    }
+
+    /// Regresa el índice del primer marco de la lista que es candidato
+    fn find_page_to_replace_among(
+        &mut self,
+        _real_memory: &mut [Option<ProcessPage>],
+        candidates: &BTreeSet<usize>,
+    ) -> usize {
+        self.frames
+            .iter()
+            .find(|frame| candidates.contains(frame))
+            .unwrap()
+    }
+
+    /// Regresa un set de índices de marcos que se deberían reemplazar dependiendo del tiempo
+    /// de acceso de cada página, que son los primeros `n` de la lista
+    fn find_n_pages_to_replace(
+        &mut self,
+        _real_memory: &mut [Option<ProcessPage>],
+        n: usize,
+    ) -> BTreeSet<usize> {
+        self.frames.iter().take(n).collect()
+    }
+}
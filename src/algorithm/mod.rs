@@ -0,0 +1,209 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::error::SimulationError;
+use crate::instruction::Instruction;
+use crate::process::ProcessPage;
+use crate::time::Time;
+
+mod clock;
+mod fifo;
+mod frame_list;
+mod lru;
+mod nru;
+mod optimal;
+mod random;
+
+pub use clock::ClockPolicy;
+pub use fifo::FifoPolicy;
+pub use lru::LruPolicy;
+pub use nru::NruPolicy;
+pub use optimal::OptimalPolicy;
+pub use random::RandomPolicy;
+
+/// Interfaz que debe implementar cualquier política de reemplazo de páginas.
+/// El sistema avisa a la política de cada evento relevante de la memoria real por medio de los
+/// hooks on_*, para que la política mantenga su propia contabilidad, y le pide a la política
+/// qué marcos reemplazar cuando la memoria real está llena.
+/// Todos los hooks tienen una implementación vacía por defecto, por lo que una política sólo
+/// necesita implementar los que le interesan
+pub trait ReplacementPolicy: fmt::Debug {
+    /// Se llama antes de ejecutar las instrucciones con la lista completa de instrucciones
+    /// válidas que se van a ejecutar (en orden) y el tamaño de página del sistema, para las
+    /// políticas que necesitan conocer los accesos futuros
+    fn prepare(&mut self, _trace: &[Instruction], _page_size: usize) {}
+
+    /// Se llama al inicio de cada instrucción que recibe el sistema
+    fn on_instruction(&mut self, _instruction: &Instruction) {}
+
+    /// Se llama cuando una página se carga en el marco `frame` de la memoria real, ya sea
+    /// desde disco (instrucción P) o desde el espacio swap (swap-in)
+    fn on_load(&mut self, _frame: usize, _page: &ProcessPage) {}
+
+    /// Se llama cuando se accede a la página que se encuentra en el marco `frame`
+    fn on_access(&mut self, _frame: usize, _page: &ProcessPage) {}
+
+    /// Se llama cuando se modifica la página que se encuentra en el marco `frame`
+    fn on_modify(&mut self, _frame: usize, _page: &ProcessPage) {}
+
+    /// Se llama cuando la página del marco `frame` se saca de la memoria real hacia el
+    /// espacio swap
+    fn on_evict(&mut self, _frame: usize, _page: &ProcessPage) {}
+
+    /// Se llama cuando la página del marco `frame` se libera por una instrucción L
+    fn on_free(&mut self, _frame: usize, _page: &ProcessPage) {}
+
+    /// Se llama cuando el sistema se reinicia con una instrucción F, después de vaciar las
+    /// memorias y regresar el tiempo a cero, para que la política olvide su contabilidad. No se
+    /// vuelve a llamar prepare, por lo que la lista de instrucciones sigue siendo la misma
+    fn reset(&mut self) {}
+
+    /// Se llama cada vez que avanza el tiempo del sistema, con el tiempo actual y la memoria
+    /// real para las políticas que actualizan los bits de las páginas periódicamente
+    fn on_time_advanced(&mut self, _now: Time, _real_memory: &mut [Option<ProcessPage>]) {}
+
+    /// Regresa el índice del marco de la memoria real que se debería reemplazar.
+    /// Sólo se llama cuando la memoria real está llena. Recibe la memoria real para las
+    /// políticas que usan los bits de las páginas (y pueden limpiarlos, como el reloj)
+    fn find_page_to_replace(&mut self, real_memory: &mut [Option<ProcessPage>]) -> usize;
+
+    /// Igual que find_page_to_replace, pero sólo puede elegir alguno de los marcos del set
+    /// `candidates`, que no está vacío y sólo tiene marcos ocupados. Se usa con el reemplazo
+    /// local, en el que un proceso sólo puede reemplazar sus propias páginas
+    fn find_page_to_replace_among(
+        &mut self,
+        real_memory: &mut [Option<ProcessPage>],
+        candidates: &BTreeSet<usize>,
+    ) -> usize;
+
+    /// Regresa un set de `n` índices de marcos de la memoria real que se deberían reemplazar
+    fn find_n_pages_to_replace(
+        &mut self,
+        real_memory: &mut [Option<ProcessPage>],
+        n: usize,
+    ) -> BTreeSet<usize>;
+}
+
+/// Opciones de configuración que reciben las funciones que construyen las políticas:
+/// - nru_reset_interval: cada cuánto tiempo la política NRU apaga los bits de referencia
+#[derive(Debug, Clone)]
+pub struct PolicyOptions {
+    pub nru_reset_interval: Time,
+}
+
+impl Default for PolicyOptions {
+    fn default() -> Self {
+        PolicyOptions {
+            nru_reset_interval: Time::from_miliseconds(5000),
+        }
+    }
+}
+
+/// Firma de las funciones que construyen una nueva instancia de una política
+pub type PolicyFactory = fn(&PolicyOptions) -> Box<dyn ReplacementPolicy>;
+
+/// Usamos este enum para listar los algoritmos que vienen incluídos con el simulador
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageReplacementAlgorithm {
+    FIFO,
+    LRU,
+    Random,
+    Optimal,
+    Clock,
+    NRU,
+}
+
+impl PageReplacementAlgorithm {
+    /// Lista de todas las variantes del enum
+    pub const ALL: [PageReplacementAlgorithm; 6] = [
+        PageReplacementAlgorithm::FIFO,
+        PageReplacementAlgorithm::LRU,
+        PageReplacementAlgorithm::Random,
+        PageReplacementAlgorithm::Optimal,
+        PageReplacementAlgorithm::Clock,
+        PageReplacementAlgorithm::NRU,
+    ];
+
+    /// Esta función se aplica a una variante del enum y regresa un string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PageReplacementAlgorithm::FIFO => "fifo",
+            PageReplacementAlgorithm::LRU => "lru",
+            PageReplacementAlgorithm::Random => "rand",
+            PageReplacementAlgorithm::Optimal => "opt",
+            PageReplacementAlgorithm::Clock => "clock",
+            PageReplacementAlgorithm::NRU => "nru",
+        }
+    }
+
+    /// Esta función se aplica a una variante del enum y regresa la función que construye la
+    /// política correspondiente
+    pub fn factory(&self) -> PolicyFactory {
+        match self {
+            PageReplacementAlgorithm::FIFO => |_| Box::new(FifoPolicy::new()),
+            PageReplacementAlgorithm::LRU => |_| Box::new(LruPolicy::new()),
+            PageReplacementAlgorithm::Random => |_| Box::new(RandomPolicy::new()),
+            PageReplacementAlgorithm::Optimal => |_| Box::new(OptimalPolicy::new()),
+            PageReplacementAlgorithm::Clock => |_| Box::new(ClockPolicy::new()),
+            PageReplacementAlgorithm::NRU => {
+                |options| Box::new(NruPolicy::new(options.nru_reset_interval))
+            }
+        }
+    }
+}
+
+/// Registro de las políticas de reemplazo disponibles, que asocia el nombre con el que se
+/// selecciona una política en la consola con la función que la construye.
+/// Para agregar una política nueva basta con registrarla aquí, sin tocar el sistema ni la
+/// interfaz de consola
+pub struct PolicyRegistry {
+    entries: Vec<(&'static str, PolicyFactory)>,
+}
+
+impl PolicyRegistry {
+    /// Constructor de un registro vacío
+    pub fn new() -> Self {
+        PolicyRegistry {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Registra una política con el nombre proporcionado. Si ya existía una política con ese
+    /// nombre se reemplaza su función constructora
+    pub fn register(&mut self, name: &'static str, factory: PolicyFactory) {
+        match self.entries.iter_mut().find(|(entry, _)| *entry == name) {
+            Some(entry) => entry.1 = factory,
+            None => self.entries.push((name, factory)),
+        }
+    }
+
+    /// Regresa los nombres de las políticas registradas en el orden en el que se registraron
+    pub fn names(&self) -> Vec<&'static str> {
+        self.entries.iter().map(|(name, _)| *name).collect()
+    }
+
+    /// Construye una nueva instancia de la política con el nombre proporcionado, pasándole las
+    /// opciones de configuración. Regresa un error si no hay una política con ese nombre
+    pub fn create(
+        &self,
+        name: &str,
+        options: &PolicyOptions,
+    ) -> Result<Box<dyn ReplacementPolicy>, SimulationError> {
+        self.entries
+            .iter()
+            .find(|(entry, _)| *entry == name)
+            .map(|(_, factory)| factory(options))
+            .ok_or_else(|| SimulationError::UnknownAlgorithm(name.to_string()))
+    }
+}
+
+/// Por defecto el registro contiene todos los algoritmos incluídos con el simulador
+impl Default for PolicyRegistry {
+    fn default() -> Self {
+        let mut registry = PolicyRegistry::new();
+        PageReplacementAlgorithm::ALL
+            .iter()
+            .for_each(|algorithm| registry.register(algorithm.as_str(), algorithm.factory()));
+        registry
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::BTreeSet;
+
+use super::ReplacementPolicy;
+use crate::process::ProcessPage;
+use crate::time::Time;
+
+/// Política Not Recently Used (segunda oportunidad mejorada): reemplaza una página de la clase
+/// más baja que tenga páginas, empezando a buscar desde una manecilla que avanza como en el reloj.
+/// Cada `reset_interval` de tiempo simulado se apagan los bits de referencia de todas las páginas.
+/// Las páginas se clasifican según sus bits de referencia y modificación:
+/// - clase 0: no referenciada, no modificada
+/// - clase 1: no referenciada, modificada
+/// - clase 2: referenciada, no modificada
+/// - clase 3: referenciada, modificada
+///
+/// Guarda:
+/// - reset_interval: cada cuánto tiempo se apagan los bits de referencia
+/// - last_reset: tiempo del sistema en el que se apagaron los bits por última vez
+/// - hand: índice del marco desde el que se empieza a buscar la siguiente página
+#[derive(Debug)]
+pub struct NruPolicy {
+    reset_interval: Time,
+    last_reset: Time,
+    hand: usize,
+}
+
+impl NruPolicy {
+    /// Constructor de la política, recibe cada cuánto tiempo se apagan los bits de referencia
+    pub fn new(reset_interval: Time) -> Self {
+        NruPolicy {
+            reset_interval,
+            last_reset: Time::new(),
+            hand: 0,
+        }
+    }
+
+    /// Regresa la clase de la página (de 0 a 3)
+    fn class_of(page: &ProcessPage) -> u8 {
+        2 * page.is_referenced() as u8 + page.is_dirty() as u8
+    }
+
+    /// Regresa el índice del primer marco candidato (a partir de la manecilla) con la página de
+    /// clase más baja, y deja la manecilla en el siguiente marco
+    fn next_victim(
+        &mut self,
+        real_memory: &[Option<ProcessPage>],
+        is_candidate: impl Fn(usize) -> bool,
+    ) -> usize {
+        let frame_index = (0..real_memory.len())
+            .map(|offset| (self.hand + offset) % real_memory.len()) // Recorremos desde la manecilla
+            .filter(|&frame_index| is_candidate(frame_index))
+            .filter_map(|frame_index| {
+                real_memory[frame_index]
+                    .as_ref()
+                    .map(|page| (NruPolicy::class_of(page), frame_index))
+            })
+            .min_by_key(|(class, _)| *class) // min_by_key regresa el primero de los mínimos
+            .map(|(_, frame_index)| frame_index)
+            .unwrap();
+        self.hand = (frame_index + 1) % real_memory.len();
+        frame_index
+    }
+}
+
+impl ReplacementPolicy for NruPolicy {
+    /// Regresa la manecilla al inicio y el último reinicio de los bits al tiempo cero
+    fn reset(&mut self) {
+        self.last_reset = Time::new();
+        self.hand = 0;
+    }
+
+    /// Si ya pasó el intervalo desde el último reinicio, apaga los bits de referencia de todas
+    /// las páginas en memoria real
+    fn on_time_advanced(&mut self, now: Time, real_memory: &mut [Option<ProcessPage>]) {
+        if self.reset_interval == Time::new() || now - self.last_reset < self.reset_interval {
+            return;
+        }
+        real_memory
+            .iter_mut()
+            .flatten()
+            .for_each(|page| page.set_referenced(false));
+        self.last_reset = now;
+    }
+
+    /// Regresa el índice de un marco con una página de la clase más baja
+    fn find_page_to_replace(&mut self, real_memory: &mut [Option<ProcessPage>]) -> usize {
+        self.next_victim(real_memory, |_| true)
+    }
+
+    /// Regresa el índice de un marco candidato con una página de la clase más baja
+    fn find_page_to_replace_among(
+        &mut self,
+        real_memory: &mut [Option<ProcessPage>],
+        candidates: &BTreeSet<usize>,
+    ) -> usize {
+        self.next_victim(real_memory, |frame_index| candidates.contains(&frame_index))
+    }
+
+    /// Regresa un set con los índices de `n` marcos, tomando las páginas de las clases más bajas
+    fn find_n_pages_to_replace(
+        &mut self,
+        real_memory: &mut [Option<ProcessPage>],
+        n: usize,
+    ) -> BTreeSet<usize> {
+        let mut page_indexes = BTreeSet::new();
+        for _ in 0..n {
+            let frame_index = self.next_victim(real_memory, |frame_index| {
+                !page_indexes.contains(&frame_index)
+            });
+            page_indexes.insert(frame_index);
+        }
+        page_indexes
+    }
+}
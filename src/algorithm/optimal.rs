@@ -0,0 +1,141 @@
+use std::collections::{BTreeSet, HashMap};
+
+use super::ReplacementPolicy;
+use crate::instruction::Instruction;
+use crate::process::{ProcessPage, PID};
+
+/// Política óptima (MIN de Belady): reemplaza la página cuyo siguiente acceso está más lejos en
+/// el futuro, o que ya no se vuelve a acceder. No se puede implementar en un sistema real porque
+/// necesita conocer las instrucciones futuras, pero sirve como cota inferior para comparar las
+/// demás políticas.
+/// Guarda:
+/// - accesses: tabla de hash que mapea (pid, índice de página) - posiciones en el trace de las
+///   instrucciones A que acceden a esa página, en orden
+/// - frees: tabla de hash que mapea pid - posiciones en el trace de las instrucciones L del pid
+/// - ends: posiciones en el trace de las instrucciones F, que reinician el sistema
+/// - position: número de instrucciones que ya procesó el sistema
+/// - resident: tabla de hash que mapea índice de marco - (pid, índice de página) de la página
+///   que se encuentra en ese marco
+#[derive(Debug, Default)]
+pub struct OptimalPolicy {
+    accesses: HashMap<(PID, usize), Vec<usize>>,
+    frees: HashMap<PID, Vec<usize>>,
+    ends: Vec<usize>,
+    position: usize,
+    resident: HashMap<usize, (PID, usize)>,
+}
+
+impl OptimalPolicy {
+    /// Constructor de la política sin trace y sin marcos ocupados
+    pub fn new() -> Self {
+        OptimalPolicy::default()
+    }
+
+    /// Regresa la posición en el trace del siguiente acceso a la página, o None si la página no
+    /// se vuelve a acceder antes de que se libere su proceso o se reinicie el sistema
+    fn next_use(&self, page_info: &(PID, usize)) -> Option<usize> {
+        // Buscamos la primera posición mayor o igual a la de la siguiente instrucción
+        let first_after = |positions: &Vec<usize>| -> Option<usize> {
+            positions
+                .get(positions.partition_point(|&pos| pos < self.position))
+                .copied()
+        };
+
+        let next_access = self.accesses.get(page_info).and_then(first_after)?;
+        let next_free = self.frees.get(&page_info.0).and_then(first_after);
+        let next_end = first_after(&self.ends);
+        match next_free.into_iter().chain(next_end).min() {
+            // Si el proceso se libera o el sistema se reinicia antes del siguiente acceso, el
+            // acceso pertenece a otro proceso con el mismo pid
+            Some(next_release) if next_release < next_access => None,
+            _ => Some(next_access),
+        }
+    }
+
+    /// Regresa una lista de los marcos ocupados ordenados de la página que se debería
+    /// reemplazar primero a la última. En caso de empate se prefiere el marco con menor índice
+    fn frames_by_next_use(&self) -> Vec<usize> {
+        let mut frames: Vec<(Option<usize>, usize)> = self
+            .resident
+            .iter()
+            .map(|(&frame, page_info)| (self.next_use(page_info), frame))
+            .collect();
+        // None (nunca se vuelve a usar) va primero, después las posiciones de mayor a menor
+        frames.sort_unstable_by(|(a_next, a_frame), (b_next, b_frame)| {
+            let a_key = a_next.unwrap_or(usize::MAX);
+            let b_key = b_next.unwrap_or(usize::MAX);
+            b_key.cmp(&a_key).then(a_frame.cmp(b_frame))
+        });
+        frames.into_iter().map(|(_, frame)| frame).collect()
+    }
+}
+
+impl ReplacementPolicy for OptimalPolicy {
+    /// Guarda las posiciones de los accesos y liberaciones de cada página del trace
+    fn prepare(&mut self, trace: &[Instruction], page_size: usize) {
+        self.accesses.clear();
+        self.frees.clear();
+        self.ends.clear();
+        trace
+            .iter()
+            .enumerate()
+            .for_each(|(position, instruction)| match instruction {
+                Instruction::Access { pid, address, .. } => self
+                    .accesses
+                    .entry((*pid, address / page_size))
+                    .or_default()
+                    .push(position),
+                Instruction::Free { pid } => self.frees.entry(*pid).or_default().push(position),
+                Instruction::End() => self.ends.push(position),
+                _ => (),
+            });
+    }
+
+    fn on_instruction(&mut self, _instruction: &Instruction) {
+        self.position += 1;
+    }
+
+    fn on_load(&mut self, frame: usize, page: &ProcessPage) {
+        self.resident.insert(frame, page.get_page_info());
+    }
+
+    /// Olvida los marcos ocupados, pero conserva las posiciones del trace
+    fn reset(&mut self) {
+        self.resident.clear();
+    }
+
+    fn on_evict(&mut self, frame: usize, _page: &ProcessPage) {
+        self.resident.remove(&frame);
+    }
+
+    fn on_free(&mut self, frame: usize, _page: &ProcessPage) {
+        self.resident.remove(&frame);
+    }
+
+    /// Regresa el índice del marco cuya página se vuelve a acceder más tarde
+    fn find_page_to_replace(&mut self, _real_memory: &mut [Option<ProcessPage>]) -> usize {
+        self.frames_by_next_use()[0]
+    }
+
+    /// Regresa el índice del marco candidato cuya página se vuelve a acceder más tarde
+    fn find_page_to_replace_among(
+        &mut self,
+        _real_memory: &mut [Option<ProcessPage>],
+        candidates: &BTreeSet<usize>,
+    ) -> usize {
+        self.frames_by_next_use()
+            .into_iter()
+            .find(|frame| candidates.contains(frame))
+            .unwrap()
+    }
+
+    /// Regresa un set con los índices de los `n` marcos cuyas páginas se vuelven a acceder
+    /// más tarde
+    fn find_n_pages_to_replace(
+        &mut self,
+        _real_memory: &mut [Option<ProcessPage>],
+        n: usize,
+    ) -> BTreeSet<usize> {
+        self.frames_by_next_use().into_iter().take(n).collect()
+    }
+}
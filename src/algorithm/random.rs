@@ -0,0 +1,68 @@
+use std::collections::BTreeSet;
+
+use rand::seq::{IteratorRandom, SliceRandom};
+use rand::thread_rng;
+
+use super::ReplacementPolicy;
+use crate::process::ProcessPage;
+
+/// Política aleatoria: reemplaza cualquiera de los marcos ocupados
+/// Guarda el set de marcos de la memoria real que tienen una página
+#[derive(Debug, Default)]
+pub struct RandomPolicy {
+    occupied: BTreeSet<usize>,
+}
+
+impl RandomPolicy {
+    /// Constructor de la política sin marcos ocupados
+    pub fn new() -> Self {
+        RandomPolicy::default()
+    }
+}
+
+impl ReplacementPolicy for RandomPolicy {
+    fn on_load(&mut self, frame: usize, _page: &ProcessPage) {
+        self.occupied.insert(frame);
+    }
+
+    fn reset(&mut self) {
+        self.occupied.clear();
+    }
+
+    fn on_evict(&mut self, frame: usize, _page: &ProcessPage) {
+        self.occupied.remove(&frame);
+    }
+
+    fn on_free(&mut self, frame: usize, _page: &ProcessPage) {
+        self.occupied.remove(&frame);
+    }
+
+    /// Regresa el índice del marco al que se debería reemplazar al azar
+    fn find_page_to_replace(&mut self, _real_memory: &mut [Option<ProcessPage>]) -> usize {
+        *self.occupied.iter().choose(&mut thread_rng()).unwrap()
+    }
+
+    /// Regresa el índice de uno de los marcos candidatos al azar
+    fn find_page_to_replace_among(
+        &mut self,
+        _real_memory: &mut [Option<ProcessPage>],
+        candidates: &BTreeSet<usize>,
+    ) -> usize {
+        *candidates.iter().choose(&mut thread_rng()).unwrap()
+    }
+
+    /// Regresa un set de índices de marcos que se deberían reemplazar al azar. Recibe el tamaño
+    /// del set que regresará
+    fn find_n_pages_to_replace(
+        &mut self,
+        _real_memory: &mut [Option<ProcessPage>],
+        n: usize,
+    ) -> BTreeSet<usize> {
+        let mut page_indexes: Vec<usize> = self.occupied.iter().copied().collect();
+        // Ordenamos al azar la lista de índices
+        page_indexes.shuffle(&mut thread_rng());
+        // Cortamos la lista al tamaño indicado
+        page_indexes.truncate(n);
+        page_indexes.into_iter().collect()
+    }
+}
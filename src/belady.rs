@@ -0,0 +1,118 @@
+use std::fmt;
+
+use crate::algorithm::{PolicyOptions, PolicyRegistry};
+use crate::error::SimulationError;
+use crate::instruction::Instruction;
+use crate::sweep::Sweep;
+use crate::system::SystemOptions;
+use crate::util;
+
+/// Un caso en el que agregar marcos a la memoria real aumentó el número de fallos de página:
+/// - frames: número de marcos antes y después de agregar marcos
+/// - page_faults: número de fallos de página con cada número de marcos
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Anomaly {
+    pub frames: (usize, usize),
+    pub page_faults: (usize, usize),
+}
+
+/// Guarda el resultado de buscar la anomalía de Belady en una lista de instrucciones:
+/// - algorithm: el nombre de la política de reemplazo que se usó
+/// - page_faults: número de marcos de la memoria real y número de fallos de página con ese
+///   número de marcos, ordenados por número de marcos
+/// - anomalies: cada caso en el que pasar de un número de marcos al siguiente aumentó el número
+///   de fallos de página
+#[derive(Debug, Clone)]
+pub struct BeladyAnalysis {
+    pub algorithm: String,
+    pub page_faults: Vec<(usize, usize)>,
+    pub anomalies: Vec<Anomaly>,
+}
+
+impl BeladyAnalysis {
+    /// Ejecuta la lista de instrucciones con la política proporcionada (normalmente FIFO) y con
+    /// cada uno de los números de marcos de la memoria real, usando paginación por demanda para
+    /// que los fallos de página dependan sólo del número de marcos. El resto de las opciones del
+    /// sistema se toman de `system_options`.
+    /// Regresa un error si la política no está en el registro
+    pub fn run(
+        registry: &PolicyRegistry,
+        name: &str,
+        policy_options: &PolicyOptions,
+        system_options: &SystemOptions,
+        frame_counts: &[usize],
+        trace: &[Instruction],
+    ) -> Result<Self, SimulationError> {
+        let mut frame_counts = frame_counts.to_vec();
+        frame_counts.sort_unstable();
+        frame_counts.dedup();
+        let page_size = system_options.page_size;
+        let real_memory_sizes: Vec<usize> = frame_counts
+            .iter()
+            .map(|frames| frames * page_size)
+            .collect();
+        let options = SystemOptions {
+            demand_paging: true,
+            ..system_options.clone()
+        };
+        // Ejecutamos un barrido sobre los tamaños de memoria real, en paralelo
+        let sweep = Sweep::run(
+            registry,
+            &[name],
+            policy_options,
+            &options,
+            (&[page_size], &real_memory_sizes, &[options.swap_space_size]),
+            trace,
+        )?;
+        let page_faults: Vec<(usize, usize)> = frame_counts
+            .into_iter()
+            .zip(sweep.points.iter().map(|point| point.page_faults))
+            .collect();
+        let anomalies = page_faults
+            .windows(2)
+            .filter(|pair| pair[1].1 > pair[0].1)
+            .map(|pair| Anomaly {
+                frames: (pair[0].0, pair[1].0),
+                page_faults: (pair[0].1, pair[1].1),
+            })
+            .collect();
+        Ok(BeladyAnalysis {
+            algorithm: name.to_string(),
+            page_faults,
+            anomalies,
+        })
+    }
+}
+
+/// Se imprime una tabla con los fallos de página de cada número de marcos, marcando los números
+/// de marcos con más fallos que el anterior, y después la lista de anomalías encontradas
+impl fmt::Display for BeladyAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rows = vec![vec![
+            String::from("Marcos"),
+            format!("Fallos de página ({})", self.algorithm),
+        ]];
+        for (frames, page_faults) in &self.page_faults {
+            let anomalous = self
+                .anomalies
+                .iter()
+                .any(|anomaly| anomaly.frames.1 == *frames);
+            rows.push(vec![
+                frames.to_string(),
+                format!("{}{}", page_faults, if anomalous { "  *" } else { "" }),
+            ]);
+        }
+        write!(f, "{}", util::display_table(&rows))?;
+        if self.anomalies.is_empty() {
+            return writeln!(f, "No se encontró la anomalía de Belady");
+        }
+        for anomaly in &self.anomalies {
+            writeln!(
+                f,
+                "Anomalía de Belady: con {} marcos hubo {} fallos de página, y con {} marcos hubo {}",
+                anomaly.frames.0, anomaly.page_faults.0, anomaly.frames.1, anomaly.page_faults.1,
+            )?;
+        }
+        Ok(())
+    }
+}
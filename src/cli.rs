@@ -1,61 +1,335 @@
-use crate::algorithm::PageReplacementAlgorithm;
-use clap::{App, Arg, ArgMatches};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+use memory_admin_simulator::{
+    FrameAllocation, OomHeuristic, PolicyOptions, PolicyRegistry, ReplacementScope,
+    SwapExhaustionResponse, SystemOptions, Time,
+};
 
 /// Usamos la librería clap para formar una interfaz de usuario en consola simple y con poco código
 /// Esta función regresa la instancia de una "aplicación" de clap con toda la configuración incluída
-pub fn get_app() -> clap::App<'static, 'static> {
+/// Recibe el registro de políticas para ofrecer como opciones todas las políticas registradas
+pub fn get_app(registry: &PolicyRegistry) -> clap::App<'static, 'static> {
     App::new("Memory Admin Simulator")
         .version("1.0")
         .author("Equipo 7 de Sistemas Operativos")
-        // El primer argumento es el algoritmo y hay tres opciones (declaradas en algorithm.rs)
+        // Si se usa un subcomando ya no son necesarios el algoritmo ni el archivo de la
+        // aplicación principal
+        .setting(AppSettings::SubcommandsNegateReqs)
+        // El primer argumento es el algoritmo y las opciones son las políticas registradas
+        // (las incluídas con el simulador se declaran en algorithm/mod.rs)
         .arg(
             Arg::with_name("algorithm")
                 .required(true)
-                .possible_values(&[
-                    PageReplacementAlgorithm::FIFO.as_str(),
-                    PageReplacementAlgorithm::LRU.as_str(),
-                    PageReplacementAlgorithm::Random.as_str(),
-                ])
+                .possible_values(&registry.names())
                 .help("Sets the algorithm to choose which page gets replaced in memory")
                 .takes_value(true)
                 .index(1),
         )
         // El segundo es el nombre del archivo por abrir
-        .arg(
-            Arg::with_name("file")
-                .required(true)
-                .help("Path to the file with the list of instructions to execute")
-                .takes_value(true)
-                .empty_values(false)
-                .index(2),
-        )
+        .arg(file_arg(2))
         // Los siguientes son opcionales:
-        // El tercer es el tamaño de la página en bytes
+        // Los tamaños de las memorias y las opciones de paginación y de las políticas de reemplazo
+        .args(&size_args(&SizeArgument::ALL))
+        .args(&paging_args())
+        // El formato en el que se imprimen los resultados
         .arg(
-            Arg::with_name(SizeArgument::Page.as_str())
-                .short("p")
-                .long("page-size")
-                .help("Sets the page size in bytes, defaults to 16 bytes")
+            Arg::with_name("format")
+                .long("format")
+                .possible_values(&[OutputFormat::Text.as_str(), OutputFormat::Jsonl.as_str()])
+                .default_value(OutputFormat::Text.as_str())
+                .help("Sets the output format: human readable text or one JSON object per instruction (JSON Lines)")
                 .takes_value(true),
         )
-        // El cuarto es el tamaño de la memoria real en bytes
+        // El archivo en el que se escriben las estadísticas de los procesos en CSV
         .arg(
-            Arg::with_name(SizeArgument::RealMemory.as_str())
-                .short("r")
-                .long("real-memory")
-                .help("Sets the size of the real memory in bytes, defaults to 2048 bytes")
-                .takes_value(true),
+            Arg::with_name("stats csv")
+                .long("stats-csv")
+                .value_name("path")
+                .help("Writes the statistics of every process of the F report to a CSV file, with a last row with the averages")
+                .takes_value(true)
+                .empty_values(false),
         )
-        // El quinto es el tamaño del espacio swap en bytes
-        .arg(
-            Arg::with_name(SizeArgument::SwapSpace.as_str())
-                .short("v")
-                .long("swap-space")
-                .help("Sets the size of the swap space in bytes, defaults to 4096 bytes")
-                .takes_value(true),
+        // El subcomando compare ejecuta el mismo archivo con varias políticas de reemplazo
+        .subcommand(
+            SubCommand::with_name("compare")
+                .about("Runs the file through every selected algorithm and prints a side-by-side table of the results")
+                .arg(file_arg(1))
+                .arg(algorithms_arg(registry))
+                .args(&size_args(&SizeArgument::ALL))
+                .args(&paging_args()),
+        )
+        // El subcomando sweep ejecuta el mismo archivo con varias combinaciones de tamaños
+        .subcommand(
+            SubCommand::with_name("sweep")
+                .about("Runs the file with every combination of the selected sizes and algorithms, in parallel, and prints a grid with the fault rate and the average turnaround of each one")
+                .arg(file_arg(1))
+                .arg(algorithms_arg(registry))
+                .args(&sweep_size_args())
+                .args(&paging_args()),
+        )
+        // El subcomando belady busca la anomalía de Belady con un número creciente de marcos
+        .subcommand(
+            SubCommand::with_name("belady")
+                .about("Runs the file with demand paging and an increasing number of real memory frames, and reports every case where adding frames increased the number of page faults")
+                .arg(file_arg(1))
+                .arg(
+                    Arg::with_name("algorithm")
+                        .short("a")
+                        .long("algorithm")
+                        .possible_values(&registry.names())
+                        .default_value("fifo")
+                        .help("Sets the algorithm to run")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("frames")
+                        .short("f")
+                        .long("frames")
+                        .default_value("1..32:1")
+                        .help("Sets the numbers of real memory frames to try, with the same syntax as the sizes of the sweep subcommand")
+                        .takes_value(true)
+                        .validator(|value| parse_size_list(&value).map(|_| ())),
+                )
+                .args(&size_args(&[SizeArgument::Page, SizeArgument::SwapSpace]))
+                .args(&paging_args()),
+        )
+        // El subcomando mrc calcula la curva de tasa de fallos de LRU en una sola pasada
+        .subcommand(
+            SubCommand::with_name("mrc")
+                .about("Computes in a single pass the page fault rate of LRU with demand paging for every size of real memory (miss-ratio curve), using stack distance analysis")
+                .arg(file_arg(1))
+                .args(&size_args(&[SizeArgument::Page]))
+                .arg(
+                    Arg::with_name("svg")
+                        .long("svg")
+                        .value_name("path")
+                        .help("Also writes a plot of the curve to a SVG file")
+                        .takes_value(true)
+                        .empty_values(false),
+                ),
         )
 }
 
+/// Regresa el argumento con los nombres de las políticas que se van a ejecutar en los
+/// subcomandos
+fn algorithms_arg(registry: &PolicyRegistry) -> Arg<'static, 'static> {
+    Arg::with_name("algorithms")
+        .short("a")
+        .long("algorithms")
+        .possible_values(&registry.names())
+        .help("Sets the algorithms to run, separated by commas, defaults to every algorithm")
+        .takes_value(true)
+        .multiple(true)
+        .use_delimiter(true)
+}
+
+/// Regresa el argumento con el nombre del archivo por abrir en la posición proporcionada
+fn file_arg(index: u64) -> Arg<'static, 'static> {
+    Arg::with_name("file")
+        .required(true)
+        .help("Path to the file with the list of instructions to execute")
+        .takes_value(true)
+        .empty_values(false)
+        .index(index)
+}
+
+/// Regresa los argumentos opcionales con los tamaños proporcionados (de la página y de las
+/// memorias)
+fn size_args(sizes: &[SizeArgument]) -> Vec<Arg<'static, 'static>> {
+    sizes
+        .iter()
+        .map(|arg| {
+            Arg::with_name(arg.as_str())
+                .short(arg.short())
+                .long(arg.long())
+                .help(arg.help())
+                .takes_value(true)
+        })
+        .collect()
+}
+
+/// Regresa los argumentos opcionales con los tamaños de la página y de las memorias que
+/// recibe el subcomando sweep, que aceptan listas y rangos de tamaños
+fn sweep_size_args() -> Vec<Arg<'static, 'static>> {
+    SizeArgument::ALL
+        .iter()
+        .map(|arg| {
+            Arg::with_name(arg.as_str())
+                .short(arg.short())
+                .long(arg.long())
+                .help("Sets a list of sizes in bytes separated by commas, where every item is a size or an inclusive range start..end or start..end:step (the step defaults to start), defaults to the same size as the main command")
+                .takes_value(true)
+                .validator(|value| parse_size_list(&value).map(|_| ()))
+        })
+        .collect()
+}
+
+/// Regresa los argumentos opcionales con las opciones de paginación y de las políticas de
+/// reemplazo, que comparten la aplicación principal y los subcomandos
+fn paging_args() -> Vec<Arg<'static, 'static>> {
+    vec![
+        // Si se usa paginación por demanda
+        Arg::with_name("demand paging")
+            .long("demand-paging")
+            .help("Only loads a page into real memory the first time it is accessed, instead of loading the whole process with the P instruction"),
+        // Cada cuánto tiempo la política NRU apaga los bits de referencia
+        Arg::with_name("nru tick")
+            .long("nru-tick")
+            .help("Sets every how many miliseconds of simulated time the nru algorithm clears the reference bits, defaults to 5000 ms")
+            .takes_value(true)
+            .validator(|value| parse_miliseconds(&value).map(|_| ())),
+        // Qué hace el sistema cuando no hay espacio en las memorias
+        Arg::with_name("on swap exhausted")
+            .long("on-swap-exhausted")
+            .possible_values(
+                &SwapExhaustionResponse::ALL
+                    .iter()
+                    .map(|response| response.as_str())
+                    .collect::<Vec<_>>(),
+            )
+            .default_value(SwapExhaustionResponse::Reject.as_str())
+            .help("Sets what happens when there isn't enough free space in both memories for a process or a page: reject the instruction, kill processes chosen with the --oom-victim heuristic until there is space, or block the process until an L instruction frees enough space")
+            .takes_value(true),
+        // Cómo elige el OOM killer el proceso que se termina
+        Arg::with_name("oom victim")
+            .long("oom-victim")
+            .possible_values(
+                &OomHeuristic::ALL
+                    .iter()
+                    .map(|heuristic| heuristic.as_str())
+                    .collect::<Vec<_>>(),
+            )
+            .default_value(OomHeuristic::Largest.as_str())
+            .help("Sets how --on-swap-exhausted kill chooses the process to kill: the one with the most pages in real memory, the newest one, the one with the lowest priority, or the one with the highest badness score (share of frames used divided by priority)")
+            .takes_value(true),
+        // De qué marcos puede tomar un proceso la página que se reemplaza
+        Arg::with_name("replacement scope")
+            .long("replacement-scope")
+            .possible_values(
+                &ReplacementScope::ALL
+                    .iter()
+                    .map(|scope| scope.as_str())
+                    .collect::<Vec<_>>(),
+            )
+            .default_value(ReplacementScope::Global.as_str())
+            .help("Sets which pages a page fault can replace when real memory is full: any page (global), or only pages of the same process (local), falling back to any page if the process has no pages in real memory")
+            .takes_value(true),
+        // Cómo se reparten los marcos de la memoria real entre los procesos
+        Arg::with_name("frame allocation")
+            .long("frame-allocation")
+            .possible_values(
+                &FrameAllocation::ALL
+                    .iter()
+                    .map(|allocation| allocation.as_str())
+                    .collect::<Vec<_>>(),
+            )
+            .default_value(FrameAllocation::NoQuotas.as_str())
+            .help("Gives every process a quota of real memory frames, recalculated when a process is created or freed: the same for every process (equal), proportional to its size, or proportional to its priority. A process that reaches its quota replaces its own pages")
+            .takes_value(true),
+    ]
+}
+
+/// Esta función recibe una referencia a un objeto de coincidencias que genera clap y
+/// regresa los nombres de los algoritmos que se eligieron para comparar, o todos los registrados
+/// si no se eligió ninguno
+pub fn get_algorithms<'a>(matches: &'a ArgMatches, registry: &PolicyRegistry) -> Vec<&'a str> {
+    match matches.values_of("algorithms") {
+        Some(values) => values.collect(),
+        None => registry.names(),
+    }
+}
+
+/// Esta función recibe una referencia a un objeto de coincidencias que genera clap y
+/// regresa la lista de números de marcos que se eligió (clap ya validó que se pueda parsear)
+pub fn get_frame_counts(matches: &ArgMatches) -> Vec<usize> {
+    parse_size_list(matches.value_of("frames").unwrap()).unwrap()
+}
+
+/// Esta función recibe una referencia a un objeto de coincidencias que genera clap y
+/// regresa el nombre del algoritmo que se eligió
+pub fn get_algorithm<'a>(matches: &'a ArgMatches) -> &'a str {
+    matches.value_of("algorithm").unwrap()
+}
+
+/// Esta función recibe una referencia a un objeto de coincidencias que genera clap y
+/// regresa las opciones de configuración de las políticas de reemplazo. Las opciones que no se
+/// pasaron se dejan con su valor por defecto (clap ya validó que se puedan parsear)
+pub fn get_policy_options(matches: &ArgMatches) -> PolicyOptions {
+    let mut options = PolicyOptions::default();
+    if let Some(value) = matches.value_of("nru tick") {
+        options.nru_reset_interval = Time::from_miliseconds(parse_miliseconds(value).unwrap());
+    }
+    options
+}
+
+/// Esta función recibe una referencia a un objeto de coincidencias que genera clap y
+/// regresa las opciones de configuración del sistema
+pub fn get_system_options(matches: &ArgMatches) -> SystemOptions {
+    SystemOptions {
+        page_size: get_size(matches, SizeArgument::Page),
+        real_memory_size: get_size(matches, SizeArgument::RealMemory),
+        swap_space_size: get_size(matches, SizeArgument::SwapSpace),
+        demand_paging: matches.is_present("demand paging"),
+        swap_exhaustion: SwapExhaustionResponse::ALL
+            .iter()
+            .copied()
+            .find(|response| matches.value_of("on swap exhausted") == Some(response.as_str()))
+            .unwrap_or_default(),
+        oom_heuristic: OomHeuristic::ALL
+            .iter()
+            .copied()
+            .find(|heuristic| matches.value_of("oom victim") == Some(heuristic.as_str()))
+            .unwrap_or_default(),
+        replacement_scope: ReplacementScope::ALL
+            .iter()
+            .copied()
+            .find(|scope| matches.value_of("replacement scope") == Some(scope.as_str()))
+            .unwrap_or_default(),
+        frame_allocation: FrameAllocation::ALL
+            .iter()
+            .copied()
+            .find(|allocation| matches.value_of("frame allocation") == Some(allocation.as_str()))
+            .unwrap_or_default(),
+    }
+}
+
+/// Usamos este enum para diferenciar entre los formatos de salida
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Jsonl,
+}
+
+impl OutputFormat {
+    /// Esta función se le aplica a una variante del enum y regresa su representación en string
+    fn as_str(&self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// Esta función recibe una referencia a un objeto de coincidencias que genera clap y
+/// regresa el formato de salida que se eligió
+pub fn get_format(matches: &ArgMatches) -> OutputFormat {
+    match matches.value_of("format") {
+        Some("jsonl") => OutputFormat::Jsonl,
+        _ => OutputFormat::Text,
+    }
+}
+
+/// Esta función recibe una referencia a un objeto de coincidencias que genera clap y
+/// regresa la ruta del archivo CSV de estadísticas, si se incluyó
+pub fn get_stats_csv<'a>(matches: &'a ArgMatches) -> Option<&'a str> {
+    matches.value_of("stats csv")
+}
+
+/// Esta función recibe una referencia a un objeto de coincidencias que genera clap y
+/// regresa la ruta del archivo SVG de la curva de tasa de fallos, si se incluyó
+pub fn get_svg<'a>(matches: &'a ArgMatches) -> Option<&'a str> {
+    matches.value_of("svg")
+}
+
 /// Esta función recibe una referencia a un objeto de coincidencias que genera clap y
 /// regresa el nombre del archivo que se incluyó
 pub fn get_filename<'a>(matches: &'a ArgMatches) -> &'a str {
@@ -78,6 +352,13 @@ pub enum SizeArgument {
 }
 
 impl SizeArgument {
+    /// Lista de todas las variantes del enum
+    const ALL: [SizeArgument; 3] = [
+        SizeArgument::Page,
+        SizeArgument::RealMemory,
+        SizeArgument::SwapSpace,
+    ];
+
     /// Esta función se le aplica a una variante del enum y regresa su representación en string
     fn as_str(&self) -> &'static str {
         match self {
@@ -87,6 +368,37 @@ impl SizeArgument {
         }
     }
 
+    /// Esta función se le aplica a una variante del enum y regresa la letra de su opción
+    fn short(&self) -> &'static str {
+        match self {
+            SizeArgument::Page => "p",
+            SizeArgument::RealMemory => "r",
+            SizeArgument::SwapSpace => "v",
+        }
+    }
+
+    /// Esta función se le aplica a una variante del enum y regresa el nombre largo de su opción
+    fn long(&self) -> &'static str {
+        match self {
+            SizeArgument::Page => "page-size",
+            SizeArgument::RealMemory => "real-memory",
+            SizeArgument::SwapSpace => "swap-space",
+        }
+    }
+
+    /// Esta función se le aplica a una variante del enum y regresa la ayuda de su opción
+    fn help(&self) -> &'static str {
+        match self {
+            SizeArgument::Page => "Sets the page size in bytes, defaults to 16 bytes",
+            SizeArgument::RealMemory => {
+                "Sets the size of the real memory in bytes, defaults to 2048 bytes"
+            }
+            SizeArgument::SwapSpace => {
+                "Sets the size of the swap space in bytes, defaults to 4096 bytes"
+            }
+        }
+    }
+
     /// Esta función se le aplica a una variante del enum y regresa el tamaño por defecto
     fn default(&self) -> usize {
         match self {
@@ -110,3 +422,46 @@ pub fn get_size(matches: &ArgMatches, arg: SizeArgument) -> usize {
     }
     arg.default()
 }
+
+/// A esta función se le pasa una referencia al objeto de coincidencias de clap y qué tipo de
+/// argumento se busca, y regresa la lista de tamaños del argumento (clap ya validó que se pueda
+/// parsear), o una lista con el número por defecto del argumento
+pub fn get_size_list(matches: &ArgMatches, arg: SizeArgument) -> Vec<usize> {
+    match matches.value_of(arg.as_str()) {
+        Some(value) => parse_size_list(value).unwrap(),
+        None => vec![arg.default()],
+    }
+}
+
+/// Parsea un número de milisegundos de tiempo simulado
+fn parse_miliseconds(value: &str) -> Result<u32, String> {
+    value
+        .trim()
+        .parse::<u32>()
+        .map_err(|_| format!("\"{}\" is not a valid number of miliseconds", value))
+}
+
+/// Parsea una lista de tamaños separados por comas, en la que cada elemento es un tamaño o un
+/// rango inclusivo inicio..fin o inicio..fin:paso (si no se incluye, el paso es el inicio)
+fn parse_size_list(value: &str) -> Result<Vec<usize>, String> {
+    let parse = |string: &str| match string.trim().parse::<usize>() {
+        Ok(0) => Err(String::from("Sizes must be greater than zero")),
+        Ok(size) => Ok(size),
+        Err(_) => Err(format!("\"{}\" is not a valid size", string)),
+    };
+    let mut sizes = Vec::new();
+    for item in value.split(',') {
+        match item.split_once("..") {
+            Some((start, rest)) => {
+                let start = parse(start)?;
+                let (end, step) = match rest.split_once(':') {
+                    Some((end, step)) => (parse(end)?, parse(step)?),
+                    None => (parse(rest)?, start),
+                };
+                sizes.extend((start..=end).step_by(step));
+            }
+            None => sizes.push(parse(item)?),
+        }
+    }
+    Ok(sizes)
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::algorithm::{PolicyOptions, PolicyRegistry};
+use crate::error::SimulationError;
+use crate::instruction::Instruction;
+use crate::process::PID;
+use crate::system::{ProcessReport, Report, System, SystemOptions};
+use crate::util;
+
+/// Guarda el resultado de ejecutar la misma lista de instrucciones con varias políticas de
+/// reemplazo:
+/// - runs: el nombre de cada política con el reporte de cada escenario de su sistema, en el
+///   orden en el que se ejecutaron. Todas las políticas tienen el mismo número de escenarios
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    pub runs: Vec<(String, Vec<Report>)>,
+}
+
+impl Comparison {
+    /// Ejecuta la lista de instrucciones en un sistema nuevo por cada política con los nombres
+    /// proporcionados, y guarda el reporte de cada escenario de cada uno.
+    /// Regresa un error si alguno de los nombres no está en el registro
+    pub fn run(
+        registry: &PolicyRegistry,
+        names: &[&str],
+        policy_options: &PolicyOptions,
+        system_options: &SystemOptions,
+        trace: &[Instruction],
+    ) -> Result<Self, SimulationError> {
+        let mut runs = Vec::new();
+        for name in names {
+            let mut system = System::new(registry.create(name, policy_options)?, system_options);
+            runs.push((name.to_string(), system.run_scenarios(trace)));
+        }
+        Ok(Comparison { runs })
+    }
+
+    /// Regresa los procesos del reporte junto con una llave (pid, número de proceso con ese pid),
+    /// que identifica al mismo proceso en los reportes de las demás políticas aunque aparezca en
+    /// otra posición. Los procesos con el mismo pid no viven al mismo tiempo, por lo que se
+    /// numeran en orden de nacimiento en cualquier reporte
+    fn key_processes(report: &Report) -> Vec<((PID, usize), &ProcessReport)> {
+        let mut occurrences: HashMap<PID, usize> = HashMap::new();
+        report
+            .processes
+            .iter()
+            .map(|process| {
+                let occurrence = occurrences.entry(process.pid).or_default();
+                *occurrence += 1;
+                ((process.pid, *occurrence), process)
+            })
+            .collect()
+    }
+
+    /// Regresa una tabla con un renglón por proceso liberado en el escenario con el índice
+    /// proporcionado y, por cada política, las columnas de turnaround, swap-ins, swap-outs y
+    /// fallos de página, y un último renglón con el turnaround promedio y la suma de los
+    /// contadores
+    fn display_scenario(&self, scenario_index: usize) -> String {
+        const COLUMNS: [&str; 4] = ["turnaround", "swap-ins", "swap-outs", "fallos"];
+        let reports: Vec<&Report> = self
+            .runs
+            .iter()
+            .map(|(_, reports)| &reports[scenario_index])
+            .collect();
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        // Los encabezados: el nombre de cada política sobre su primera columna y el nombre de
+        // cada columna
+        let mut names = vec![String::new()];
+        let mut headers = vec![String::from("Proceso")];
+        for (name, _) in &self.runs {
+            names.push(name.clone());
+            names.extend((1..COLUMNS.len()).map(|_| String::new()));
+            headers.extend(COLUMNS.iter().map(|column| column.to_string()));
+        }
+        rows.push(names);
+        rows.push(headers);
+        // Las políticas pueden liberar los procesos en otro orden (por ejemplo, si terminan
+        // procesos distintos por falta de memoria), así que los renglones se forman por proceso
+        // y no por posición en el reporte: primero en el orden de la primera política, y después
+        // los procesos que sólo aparecen en los reportes de las demás
+        let keyed_reports: Vec<Vec<((PID, usize), &ProcessReport)>> = reports
+            .iter()
+            .map(|report| Comparison::key_processes(report))
+            .collect();
+        let mut keys: Vec<(PID, usize)> = Vec::new();
+        for (key, _) in keyed_reports.iter().flatten() {
+            if !keys.contains(key) {
+                keys.push(*key);
+            }
+        }
+        for key in keys {
+            let mut row = vec![key.0.to_string()];
+            for keyed_processes in &keyed_reports {
+                let process = keyed_processes
+                    .iter()
+                    .find(|(process_key, _)| *process_key == key)
+                    .map(|(_, process)| process);
+                match process {
+                    Some(process) => row.extend(vec![
+                        process.turnaround.to_string(),
+                        process.swap_ins.to_string(),
+                        process.swap_outs.to_string(),
+                        process.page_faults.to_string(),
+                    ]),
+                    None => row.extend((0..COLUMNS.len()).map(|_| String::new())),
+                }
+            }
+            rows.push(row);
+        }
+        let mut totals = vec![String::from("Total")];
+        for report in &reports {
+            let sum = |value: &dyn Fn(&ProcessReport) -> usize| {
+                report
+                    .processes
+                    .iter()
+                    .map(value)
+                    .sum::<usize>()
+                    .to_string()
+            };
+            totals.extend(vec![
+                match report.average_turnaround {
+                    Some(average_turnaround) => format!("{}s (prom.)", average_turnaround),
+                    None => String::from("-"),
+                },
+                sum(&|process| process.swap_ins),
+                sum(&|process| process.swap_outs),
+                sum(&|process| process.page_faults),
+            ]);
+        }
+        rows.push(totals);
+        util::display_table(&rows)
+    }
+}
+
+/// Se imprime la tabla de cada escenario. Si hay más de un escenario, cada tabla lleva el número
+/// de su escenario y se separa de la anterior con una línea vacía
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let num_scenarios = self
+            .runs
+            .first()
+            .map(|(_, reports)| reports.len())
+            .unwrap_or(0);
+        for scenario_index in 0..num_scenarios {
+            if num_scenarios > 1 {
+                if scenario_index > 0 {
+                    writeln!(f)?;
+                }
+                writeln!(f, "Escenario {}", scenario_index + 1)?;
+            }
+            write!(f, "{}", self.display_scenario(scenario_index))?;
+        }
+        Ok(())
+    }
+}
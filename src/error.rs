@@ -0,0 +1,78 @@
+use std::error;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::process::PID;
+
+/// Usamos este enum para describir los errores que pueden ocurrir al ejecutar una instrucción en
+/// el sistema, para que quien use el sistema pueda reaccionar a cada tipo de error
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum SimulationError {
+    /// Ya existe un proceso ejecutándose con el pid
+    DuplicatePid(PID),
+    /// No existe un proceso ejecutándose con el pid
+    UnknownPid(PID),
+    /// La dirección virtual no pertenece al proceso
+    AddressOutOfRange { pid: PID, address: usize },
+    /// El proceso necesita más memoria (en bytes) de la que hay disponible en el sistema
+    OutOfMemory { requested: usize, available: usize },
+    /// No hay un marco libre en el espacio swap para guardar una página
+    SwapExhausted,
+    /// La tabla de páginas de un proceso no coincide con el contenido de las memorias
+    CorruptState { pid: PID, page_index: usize },
+    /// No hay una política de reemplazo registrada con ese nombre
+    UnknownAlgorithm(String),
+    /// El proceso está bloqueado esperando a que se libere memoria
+    ProcessBlocked(PID),
+    /// El sistema ya terminó con una instrucción E y no ejecuta más instrucciones
+    SimulationFinished,
+}
+
+impl fmt::Display for SimulationError {
+    /// Usamos esta función para imprimir el mensaje de error correspondiente a cada variante
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationError::DuplicatePid(pid) => {
+                write!(f, "Ya existe un proceso ejecutándose con el pid {}", pid)
+            }
+            SimulationError::UnknownPid(pid) => {
+                write!(f, "No existe un proceso ejecutándose con el pid {}", pid)
+            }
+            SimulationError::AddressOutOfRange { pid, address } => write!(
+                f,
+                "El proceso {} no contiene la dirección virtual {}",
+                pid, address,
+            ),
+            SimulationError::OutOfMemory {
+                requested,
+                available,
+            } => write!(
+                f,
+                "El tamaño del proceso ({} bytes) es mayor a la memoria disponible en el sistema ({} bytes)",
+                requested, available,
+            ),
+            SimulationError::SwapExhausted => {
+                write!(f, "No hay marcos libres en el espacio swap")
+            }
+            SimulationError::CorruptState { pid, page_index } => write!(
+                f,
+                "No se encontró la página {} del proceso {}",
+                page_index, pid,
+            ),
+            SimulationError::UnknownAlgorithm(name) => {
+                write!(f, "Un algoritmo con el nombre {} no se ha implementado", name)
+            }
+            SimulationError::ProcessBlocked(pid) => write!(
+                f,
+                "El proceso {} está bloqueado esperando a que se libere memoria",
+                pid,
+            ),
+            SimulationError::SimulationFinished => {
+                write!(f, "La simulación ya terminó con una instrucción E")
+            }
+        }
+    }
+}
+
+impl error::Error for SimulationError {}
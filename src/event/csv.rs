@@ -0,0 +1,59 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+use super::{EventSink, SimulationEvent};
+use crate::system::Report;
+
+/// Sink que escribe el reporte de cada instrucción F en un archivo CSV (con el formato de
+/// Report::write_csv). El archivo se crea con el primer reporte, y los reportes de los
+/// siguientes escenarios se agregan al final, distinguiéndose por la columna del escenario
+/// Guarda:
+/// - path: la ruta del archivo
+/// - writer: el archivo abierto, si ya se escribió el primer reporte
+#[derive(Debug)]
+pub struct CsvSink {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+}
+
+impl CsvSink {
+    /// Constructor al que se le pasa la ruta del archivo en el que se escribirán los reportes
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        CsvSink {
+            path: path.into(),
+            writer: None,
+        }
+    }
+
+    /// Escribe los renglones del reporte, creando el archivo con el encabezado si todavía no
+    /// existe
+    fn write_report(&mut self, report: &Report) -> io::Result<()> {
+        let writer = match &mut self.writer {
+            Some(writer) => writer,
+            None => {
+                let mut writer = BufWriter::new(File::create(&self.path)?);
+                writeln!(writer, "{}", Report::CSV_HEADER)?;
+                self.writer.insert(writer)
+            }
+        };
+        report.write_csv_rows(&mut *writer)?;
+        writer.flush()
+    }
+}
+
+impl EventSink for CsvSink {
+    fn handle(&mut self, event: &SimulationEvent) {
+        if let SimulationEvent::Report(report) = event {
+            // Los sinks no pueden regresar errores, por lo que si no se pudo escribir el archivo
+            // sólo se imprime el error
+            if let Err(error) = self.write_report(report) {
+                eprintln!(
+                    "Error al escribir el archivo {}: {}",
+                    self.path.display(),
+                    error,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use serde::Serialize;
+
+use super::{EventSink, SimulationEvent};
+use crate::error::SimulationError;
+use crate::instruction::Instruction;
+use crate::system::{Frame, Report};
+use crate::time::Time;
+
+/// Objeto que se escribe por cada instrucción ejecutada:
+/// - instruction: la instrucción
+/// - time: tiempo en milisegundos que tomó la instrucción (null si hubo un error)
+/// - system_time: tiempo del sistema en milisegundos después de ejecutar la instrucción
+/// - frames: marcos de ambas memorias que se usaron en la instrucción, sin repetirse
+/// - swap_ins, swap_outs, page_faults: número de cada evento durante la instrucción
+/// - events: lista de eventos que emitió el sistema durante la instrucción
+/// - error: el error por el que no se pudo ejecutar la instrucción, con su mensaje
+/// - report: el reporte de la instrucción F
+#[derive(Debug, Serialize)]
+struct InstructionRecord {
+    instruction: Option<Instruction>,
+    time: Option<Time>,
+    system_time: Time,
+    frames: Vec<Frame>,
+    swap_ins: usize,
+    swap_outs: usize,
+    page_faults: usize,
+    events: Vec<SimulationEvent>,
+    error: Option<ErrorRecord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    report: Option<Report>,
+}
+
+/// Un error se escribe con su variante y con el mismo mensaje que imprime la consola
+#[derive(Debug, Serialize)]
+struct ErrorRecord {
+    kind: SimulationError,
+    message: String,
+}
+
+/// Sink que imprime en la consola un objeto JSON por línea (JSON Lines) por cada instrucción
+/// que ejecuta el sistema, para procesar los resultados con otros programas
+/// Guarda:
+/// - system_time: el tiempo del sistema después de la última instrucción
+/// - current: el objeto de la instrucción que se está ejecutando
+#[derive(Debug, Default)]
+pub struct JsonlSink {
+    system_time: Time,
+    current: Option<InstructionRecord>,
+}
+
+impl JsonlSink {
+    /// Constructor de un sink sin instrucciones pendientes
+    pub fn new() -> Self {
+        JsonlSink::default()
+    }
+
+    /// Regresa el objeto de la instrucción actual, creándolo si no existe
+    fn current(&mut self) -> &mut InstructionRecord {
+        let system_time = self.system_time;
+        self.current.get_or_insert_with(|| InstructionRecord {
+            instruction: None,
+            time: None,
+            system_time,
+            frames: Vec::new(),
+            swap_ins: 0,
+            swap_outs: 0,
+            page_faults: 0,
+            events: Vec::new(),
+            error: None,
+            report: None,
+        })
+    }
+
+    /// Añade un marco a la lista de marcos usados en la instrucción si no estaba
+    fn touch(&mut self, frame: Frame) {
+        let record = self.current();
+        if !record.frames.contains(&frame) {
+            record.frames.push(frame);
+        }
+    }
+
+    /// Imprime el objeto de la instrucción actual en una línea
+    fn flush(&mut self) {
+        if let Some(record) = self.current.take() {
+            println!("{}", serde_json::to_string(&record).unwrap());
+        }
+    }
+}
+
+impl EventSink for JsonlSink {
+    fn handle(&mut self, event: &SimulationEvent) {
+        match event {
+            SimulationEvent::InstructionStarted { instruction } => {
+                self.flush();
+                self.current().instruction = Some(instruction.clone());
+                return;
+            }
+            SimulationEvent::ProcessCreated { .. }
+            | SimulationEvent::ProcessKilled { .. }
+            | SimulationEvent::ProcessBlocked { .. }
+            | SimulationEvent::ProcessUnblocked { .. }
+            | SimulationEvent::UnblockFailed { .. }
+            | SimulationEvent::BlockedProcessRemoved { .. }
+            | SimulationEvent::Reset { .. } => {}
+            SimulationEvent::PageLoaded { frame, .. }
+            | SimulationEvent::PageAccessed { frame, .. } => self.touch(Frame::real(*frame)),
+            SimulationEvent::PagePlacedInSwap { swap_frame, .. } => {
+                self.touch(Frame::swap(*swap_frame))
+            }
+            SimulationEvent::PageFault { .. } => self.current().page_faults += 1,
+            SimulationEvent::SwapIn {
+                swap_frame, frame, ..
+            } => {
+                self.touch(Frame::swap(*swap_frame));
+                self.touch(Frame::real(*frame));
+                self.current().swap_ins += 1;
+            }
+            SimulationEvent::SwapOut {
+                frame, swap_frame, ..
+            } => {
+                self.touch(Frame::real(*frame));
+                self.touch(Frame::swap(*swap_frame));
+                self.current().swap_outs += 1;
+            }
+            SimulationEvent::FrameFreed { frame, .. } => self.touch(*frame),
+            SimulationEvent::InstructionFinished { time } => {
+                self.current().time = Some(*time);
+                return;
+            }
+            SimulationEvent::InstructionFailed { error } => {
+                self.current().error = Some(ErrorRecord {
+                    kind: error.clone(),
+                    message: error.to_string(),
+                });
+                self.flush();
+                return;
+            }
+            // El tiempo avanza al final de una instrucción exitosa, por lo que ahí termina su
+            // objeto
+            SimulationEvent::TimeAdvanced { now } => {
+                self.system_time = *now;
+                self.current().system_time = *now;
+                self.flush();
+                return;
+            }
+            SimulationEvent::Report(report) => {
+                self.current().report = Some(report.clone());
+                return;
+            }
+        }
+        self.current().events.push(event.clone());
+    }
+}
@@ -0,0 +1,120 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::error::SimulationError;
+use crate::instruction::Instruction;
+use crate::process::PID;
+use crate::system::{Frame, OomHeuristic, Report};
+use crate::time::Time;
+
+mod csv;
+mod jsonl;
+mod text;
+
+pub use csv::CsvSink;
+pub use jsonl::JsonlSink;
+pub use text::TextSink;
+
+/// Usamos este enum para describir lo que ocurre dentro del sistema mientras ejecuta las
+/// instrucciones. El sistema emite estos eventos a los sinks registrados en vez de imprimirlos
+/// Al serializarse cada evento se escribe como un objeto con su nombre en el campo "event"
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum SimulationEvent {
+    /// Empezó a ejecutarse una instrucción
+    InstructionStarted { instruction: Instruction },
+    /// Se creó un proceso con una instrucción P (con paginación por demanda sus páginas se
+    /// cargarán hasta que se accedan)
+    ProcessCreated {
+        pid: PID,
+        size: usize,
+        pages: usize,
+        demand_paging: bool,
+    },
+    /// Se cargó desde disco una página en el marco `frame` de la memoria real
+    PageLoaded { pid: PID, page: usize, frame: usize },
+    /// Se colocó una página de un proceso nuevo directamente en el espacio swap, porque no cabía
+    /// en la memoria real
+    PagePlacedInSwap {
+        pid: PID,
+        page: usize,
+        swap_frame: usize,
+    },
+    /// Se accedió a una página que no estaba en la memoria real
+    PageFault { pid: PID, page: usize },
+    /// Se movió una página del marco `swap_frame` del espacio swap al marco `frame` de la
+    /// memoria real
+    SwapIn {
+        pid: PID,
+        page: usize,
+        swap_frame: usize,
+        frame: usize,
+    },
+    /// Se sacó una página del marco `frame` de la memoria real. Si `write_back` es true fue
+    /// necesario escribirla en el marco `swap_frame` del espacio swap, si no ahí ya estaba una
+    /// copia válida
+    SwapOut {
+        pid: PID,
+        page: usize,
+        frame: usize,
+        swap_frame: usize,
+        write_back: bool,
+    },
+    /// Se accedió (o se modificó) la dirección virtual `address` de un proceso, que corresponde
+    /// a la dirección `real_address` en la memoria real
+    PageAccessed {
+        pid: PID,
+        address: usize,
+        page: usize,
+        frame: usize,
+        real_address: usize,
+        modifies: bool,
+    },
+    /// Se liberó un marco de alguna de las memorias con una instrucción L
+    FrameFreed { pid: PID, page: usize, frame: Frame },
+    /// Terminó de ejecutarse una instrucción, tomando el tiempo `time`
+    InstructionFinished { time: Time },
+    /// No se pudo ejecutar una instrucción
+    InstructionFailed { error: SimulationError },
+    /// Avanzó el tiempo del sistema, `now` es el tiempo actual
+    TimeAdvanced { now: Time },
+    /// No había espacio en las memorias, por lo que el OOM killer eligió con la heurística
+    /// `heuristic` al proceso y lo terminó para liberar sus marcos
+    ProcessKilled { pid: PID, heuristic: OomHeuristic },
+    /// No había espacio en las memorias para el proceso, por lo que se bloqueó hasta que una
+    /// instrucción L libere memoria
+    ProcessBlocked { pid: PID, size: usize },
+    /// Se liberó memoria suficiente para un proceso bloqueado, que se crea a continuación
+    ProcessUnblocked { pid: PID },
+    /// No se pudo crear un proceso desbloqueado, por lo que sigue al frente de la cola
+    UnblockFailed { pid: PID, error: SimulationError },
+    /// Una instrucción L sacó de la cola a un proceso bloqueado
+    BlockedProcessRemoved { pid: PID },
+    /// Se generó el reporte de la instrucción F
+    Report(Report),
+    /// El sistema se reinició después de una instrucción F, empezando el escenario `scenario`
+    Reset { scenario: usize },
+}
+
+/// Interfaz que deben implementar los receptores de los eventos del sistema
+pub trait EventSink {
+    /// Se llama con cada evento que emite el sistema, en el orden en el que ocurren
+    fn handle(&mut self, event: &SimulationEvent);
+}
+
+/// Cualquier closure que reciba una referencia a un evento se puede usar como sink, por
+/// ejemplo para guardar los eventos en una lista
+impl<F: FnMut(&SimulationEvent)> EventSink for F {
+    fn handle(&mut self, event: &SimulationEvent) {
+        self(event)
+    }
+}
+
+/// Los sinks no necesariamente implementan Debug, así que sólo imprimimos su nombre para poder
+/// derivar Debug en el sistema
+impl fmt::Debug for dyn EventSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "EventSink")
+    }
+}
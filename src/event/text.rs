@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use super::{EventSink, SimulationEvent};
+use crate::process::PID;
+use crate::system::{Memory, Report};
+use crate::util;
+
+/// Sink que imprime los eventos del sistema en la consola como texto en español.
+/// Algunos eventos de una misma instrucción se agrupan en rangos antes de imprimirse (los
+/// swap-outs de una instrucción P, las páginas colocadas en el espacio swap y los marcos
+/// liberados), por lo que se guardan hasta que termina la instrucción o llega otro tipo de evento
+/// Guarda:
+/// - grouping: si se está ejecutando una instrucción P, en la que los swap-outs se agrupan
+/// - swapped_out: rangos de páginas sacadas de la memoria real por pid
+/// - placed_in_swap: rangos de páginas colocadas directamente en el espacio swap por pid
+/// - freed_real, freed_swap: rangos de marcos liberados en cada memoria
+#[derive(Debug, Default)]
+pub struct TextSink {
+    grouping: bool,
+    swapped_out: BTreeMap<PID, Vec<Range<usize>>>,
+    placed_in_swap: BTreeMap<PID, Vec<Range<usize>>>,
+    freed_real: Vec<Range<usize>>,
+    freed_swap: Vec<Range<usize>>,
+}
+
+impl TextSink {
+    /// Constructor de un sink sin eventos pendientes
+    pub fn new() -> Self {
+        TextSink::default()
+    }
+
+    /// Imprime los swap-outs agrupados pendientes
+    fn flush_swapped_out(&mut self) {
+        for (pid, ranges) in std::mem::take(&mut self.swapped_out) {
+            if let Some(ranges_str) = util::display_ranges_vec(&ranges) {
+                println!("Swap out de páginas del proceso {}: {}", pid, ranges_str);
+            }
+        }
+    }
+
+    /// Imprime todos los eventos agrupados pendientes
+    fn flush(&mut self) {
+        self.flush_swapped_out();
+        for (pid, ranges) in std::mem::take(&mut self.placed_in_swap) {
+            if let Some(ranges_str) = util::display_ranges_vec(&ranges) {
+                println!(
+                    "Se colocaron en el espacio swap las páginas del proceso {}: {}",
+                    pid, ranges_str,
+                );
+            }
+        }
+        if let Some(ranges_str) = util::display_ranges_vec(&std::mem::take(&mut self.freed_real)) {
+            println!("Se liberan de la memoria real: {}", ranges_str);
+        }
+        if let Some(ranges_str) = util::display_ranges_vec(&std::mem::take(&mut self.freed_swap)) {
+            println!("Se liberan del espacio swap: {}", ranges_str);
+        }
+    }
+
+    /// Regresa la tasa de fallos como porcentaje, o "sin accesos" si no se pudo calcular
+    fn display_fault_rate(fault_rate: Option<f64>) -> String {
+        match fault_rate {
+            Some(fault_rate) => format!("{:.2}% de fallos", fault_rate * 100.0),
+            None => String::from("sin accesos"),
+        }
+    }
+
+    /// Imprime las estadísticas del reporte de la instrucción F
+    fn print_report(report: &Report) {
+        println!("Turnaround de cada proceso:");
+        // Por cada proceso imprimimos su vida y su turnaround, que son parciales si el proceso
+        // sigue vivo
+        report.processes.iter().for_each(|process| {
+            println!(
+                "\tProceso {}:\t{:16}\t{} de turnaround{}",
+                process.pid,
+                format!("{} - {}", process.birth, process.death),
+                process.turnaround,
+                if process.killed {
+                    " (terminado por falta de memoria)"
+                } else if process.freed {
+                    ""
+                } else {
+                    " (sigue vivo)"
+                },
+            );
+        });
+        match report.average_turnaround {
+            Some(average_turnaround) => {
+                println!("Turnaround promedio: {} segundos", average_turnaround)
+            }
+            None => println!("Turnaround promedio: sin procesos"),
+        }
+        println!("Swaps por proceso:");
+        // Por cada proceso imprimimos su número de swap-ins, swap-outs, de las veces que
+        // un swap-out tuvo que escribir la página en el espacio swap y de fallos de página
+        report.processes.iter().for_each(|process| {
+            println!(
+                "\tProceso {}:\t{} swap-ins,\t{} swap-outs,\t{} write-backs,\t{} fallos de página",
+                process.pid,
+                process.swap_ins,
+                process.swap_outs,
+                process.write_backs,
+                process.page_faults,
+            );
+        });
+        // Si el sistema reparte los marcos en cuotas imprimimos la cuota de cada proceso
+        if report
+            .processes
+            .iter()
+            .any(|process| process.quota.is_some())
+        {
+            println!("Cuota de marcos por proceso:");
+            report.processes.iter().for_each(|process| {
+                if let Some(quota) = process.quota {
+                    println!("\tProceso {}:\t{} marcos", process.pid, quota);
+                }
+            });
+        }
+        println!("Accesos por proceso:");
+        // Por cada proceso imprimimos sus hits, sus fallos de página divididos en
+        // obligatorios y de capacidad, y su tasa de fallos
+        report.processes.iter().for_each(|process| {
+            println!(
+                "\tProceso {}:\t{} hits,\t{} fallos obligatorios,\t{} fallos de capacidad,\t{}",
+                process.pid,
+                process.hits,
+                process.compulsory_faults,
+                process.capacity_faults,
+                TextSink::display_fault_rate(process.fault_rate),
+            );
+        });
+        println!(
+            "Accesos del sistema: {} hits, {} fallos de página ({} obligatorios y {} de capacidad), {}",
+            report.hits,
+            report.page_faults,
+            report.compulsory_faults,
+            report.capacity_faults,
+            TextSink::display_fault_rate(report.fault_rate),
+        );
+        if !report.blocked.is_empty() {
+            println!(
+                "Procesos bloqueados esperando memoria: {}",
+                report
+                    .blocked
+                    .iter()
+                    .map(|pid| pid.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+        }
+    }
+}
+
+impl EventSink for TextSink {
+    fn handle(&mut self, event: &SimulationEvent) {
+        match event {
+            SimulationEvent::ProcessCreated {
+                pid,
+                size,
+                pages,
+                demand_paging,
+            } => {
+                self.flush();
+                println!(
+                    "Se asignaron {} bytes ({} páginas) al proceso {}",
+                    size, pages, pid,
+                );
+                if *demand_paging {
+                    println!("Las páginas se cargarán a la memoria real cuando se accedan");
+                }
+                self.grouping = true;
+            }
+            // Las páginas que se cargan con una instrucción P no se imprimen, sólo las que se
+            // cargan por un fallo de página
+            SimulationEvent::PageLoaded { pid, page, .. } => {
+                if self.grouping {
+                    self.flush_swapped_out();
+                } else {
+                    self.flush();
+                    println!(
+                        "Fallo de página: se cargó la página {} del proceso {}",
+                        page, pid,
+                    );
+                }
+            }
+            SimulationEvent::PagePlacedInSwap { pid, page, .. } => {
+                self.flush_swapped_out();
+                util::add_index_to_vec_of_ranges(
+                    *page,
+                    self.placed_in_swap.entry(*pid).or_default(),
+                );
+            }
+            // La instrucción se imprime antes de ejecutarse desde la interfaz de consola
+            SimulationEvent::InstructionStarted { .. }
+            | SimulationEvent::PageFault { .. }
+            | SimulationEvent::TimeAdvanced { .. } => {}
+            SimulationEvent::SwapIn { pid, page, .. } => {
+                self.flush();
+                println!("Swap in de la página {} del proceso {}", page, pid);
+            }
+            SimulationEvent::SwapOut { pid, page, .. } => {
+                if self.grouping {
+                    util::add_index_to_vec_of_ranges(
+                        *page,
+                        self.swapped_out.entry(*pid).or_default(),
+                    );
+                } else {
+                    println!("Swap out de la página {} del proceso {}", page, pid);
+                }
+            }
+            SimulationEvent::PageAccessed {
+                pid,
+                address,
+                page,
+                frame,
+                real_address,
+                modifies,
+            } => {
+                self.flush();
+                println!(
+                    "Se {} la dirección {} del proceso {} (página {})",
+                    if *modifies { "modificó" } else { "accedió a" },
+                    address,
+                    pid,
+                    page,
+                );
+                println!(
+                    "Esta dirección corresponde a la dirección {} en la memoria real (marco de página {})",
+                    real_address, frame,
+                );
+            }
+            SimulationEvent::FrameFreed { frame, .. } => match frame.get_memory() {
+                Memory::Real => {
+                    util::add_index_to_vec_of_ranges(frame.get_index(), &mut self.freed_real)
+                }
+                Memory::Swap => {
+                    util::add_index_to_vec_of_ranges(frame.get_index(), &mut self.freed_swap)
+                }
+            },
+            SimulationEvent::InstructionFinished { time } => {
+                self.flush();
+                self.grouping = false;
+                println!("La instrucción tomó {}", time);
+            }
+            SimulationEvent::InstructionFailed { error } => {
+                self.flush();
+                self.grouping = false;
+                println!("Error: {}", error);
+            }
+            SimulationEvent::Report(report) => {
+                self.flush();
+                TextSink::print_report(report);
+            }
+            SimulationEvent::ProcessKilled { pid, heuristic } => {
+                self.flush();
+                println!(
+                    "No hay espacio suficiente en las memorias: se terminó el proceso {} (elegido con la heurística {}) para liberar sus marcos",
+                    pid,
+                    heuristic.as_str(),
+                );
+            }
+            SimulationEvent::ProcessBlocked { pid, size } => {
+                println!(
+                    "No hay espacio suficiente en las memorias para el proceso {} ({} bytes): se bloqueó hasta que se libere memoria",
+                    pid, size,
+                );
+            }
+            SimulationEvent::ProcessUnblocked { pid } => {
+                self.flush();
+                println!(
+                    "Se liberó memoria suficiente: se desbloqueó el proceso {}",
+                    pid
+                );
+            }
+            SimulationEvent::UnblockFailed { pid, error } => {
+                self.flush();
+                println!(
+                    "No se pudo crear el proceso desbloqueado {}, sigue en la cola: {}",
+                    pid, error
+                );
+            }
+            SimulationEvent::BlockedProcessRemoved { pid } => {
+                println!(
+                    "Se canceló el proceso {}, que estaba bloqueado esperando memoria",
+                    pid,
+                );
+            }
+            SimulationEvent::Reset { scenario } => {
+                println!(
+                    "Se reinició el simulador (memorias vacías y tiempo en 0) para el escenario {}",
+                    scenario,
+                );
+            }
+        }
+    }
+}
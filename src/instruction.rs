@@ -1,15 +1,18 @@
 use std::convert::TryFrom;
 use std::fmt;
 
-use crate::process::PID;
+use serde::Serialize;
+
+use crate::process::{DEFAULT_PRIORITY, PID};
 use crate::util;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 /// Usamos este enum para definir el grupo de peticiones que se pueden incluir en el input, así como los argumentos de cada una
 pub enum Instruction {
     Process {
         pid: PID,
         size: usize,
+        priority: u16,
     },
     Access {
         address: usize,
@@ -37,7 +40,13 @@ impl<'a> TryFrom<&'a str> for Instruction {
                     .map_err(|err_message| (value, err_message))?;
                 let pid = util::string_to_pid(line_iter.next(), "P")
                     .map_err(|err_message| (value, err_message))?;
-                Ok(Instruction::Process { pid, size })
+                let priority = util::string_to_priority(line_iter.next())
+                    .map_err(|err_message| (value, err_message))?;
+                Ok(Instruction::Process {
+                    pid,
+                    size,
+                    priority,
+                })
             }
             // En el caso de que se identifiqué correctamente la instrucción A, se retornará un outcome exitoso
             Some("A") => {
@@ -78,11 +87,28 @@ impl fmt::Display for Instruction {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // En este caso el comando match se encarga de ejecutar el código que imprimirá lo que se hará en cada solicitud
         match &self {
-            // Se imprime la cantidad de bytes que se asignarán al proceso de la solicitud
-            Instruction::Process { pid, size } => {
+            // Se imprime la cantidad de bytes que se asignarán al proceso de la solicitud, y su
+            // prioridad sólo si no es la prioridad por defecto
+            Instruction::Process {
+                pid,
+                size,
+                priority: DEFAULT_PRIORITY,
+            } => {
                 writeln!(f, "P {} {}", *size, *pid)?;
                 write!(f, "Asignar {} bytes al proceso {}", *size, *pid)
             }
+            Instruction::Process {
+                pid,
+                size,
+                priority,
+            } => {
+                writeln!(f, "P {} {} prio={}", *size, *pid, *priority)?;
+                write!(
+                    f,
+                    "Asignar {} bytes al proceso {} con prioridad {}",
+                    *size, *pid, *priority
+                )
+            }
             // Se imprime la dirección virtual y el proceso correspondiente de la que se accesará
             Instruction::Access {
                 pid,
@@ -120,8 +146,11 @@ impl fmt::Display for Instruction {
                 writeln!(f, "F")?;
                 write!(f, "Fin. Reporte de salida:")
             }
-            // Se imprime lo que representa el final del archivo
-            Instruction::Exit() => write!(f, "E"),
+            // Se imprime lo que representa el final de la simulación
+            Instruction::Exit() => {
+                writeln!(f, "E")?;
+                write!(f, "Fin de la simulación. Reporte de salida:")
+            }
         }
     }
 }
@@ -0,0 +1,50 @@
+//! Simulador de administración de memoria que compara políticas de reemplazo de páginas
+//!
+//! El simulador se usa como librería de la siguiente forma:
+//! - Se obtiene una política de reemplazo del registro de políticas (PolicyRegistry::default
+//!   contiene todas las incluídas) o se implementa el trait ReplacementPolicy
+//! - Se instancía un System con la política y las opciones del sistema (SystemOptions)
+//! - Opcionalmente se le pasa al sistema la lista completa de instrucciones con System::prepare,
+//!   necesario para la política óptima
+//! - Se ejecuta cada Instruction con System::process_instruction, que regresa el tiempo que tomó
+//!   o un SimulationError
+//! - Se registran sinks con System::add_sink para recibir los eventos del sistema (TextSink los
+//!   imprime como texto en la consola)
+//! - Se consulta el estado del sistema con sus funciones get_* y las estadísticas con
+//!   System::report
+//!
+//! Las instrucciones se pueden obtener de cada línea de un archivo con Instruction::try_from.
+//! Para ejecutar la misma lista de instrucciones con varias políticas se usa Comparison::run, y
+//! con varias combinaciones de tamaños de memoria Sweep::run. BeladyAnalysis::run busca los casos
+//! en los que agregar marcos aumenta los fallos de página, y MissRatioCurve::from_trace calcula
+//! los fallos de página de LRU con cualquier tamaño de memoria real en una sola pasada
+
+#![allow(clippy::upper_case_acronyms)]
+
+pub mod algorithm;
+pub mod belady;
+pub mod compare;
+pub mod error;
+pub mod event;
+pub mod instruction;
+pub mod mrc;
+pub mod process;
+pub mod sweep;
+pub mod system;
+pub mod time;
+mod util;
+
+pub use algorithm::{PolicyOptions, PolicyRegistry, ReplacementPolicy};
+pub use belady::{Anomaly, BeladyAnalysis};
+pub use compare::Comparison;
+pub use error::SimulationError;
+pub use event::{CsvSink, EventSink, JsonlSink, SimulationEvent, TextSink};
+pub use instruction::Instruction;
+pub use mrc::MissRatioCurve;
+pub use process::{Process, ProcessPage, PID};
+pub use sweep::{Sweep, SweepPoint};
+pub use system::{
+    Frame, FrameAllocation, Memory, OomHeuristic, ProcessReport, ReplacementScope, Report,
+    SwapExhaustionResponse, System, SystemOptions,
+};
+pub use time::Time;
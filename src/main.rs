@@ -1,59 +1,188 @@
 use std::convert::TryFrom;
 use std::fs;
 
-mod algorithm;
+use clap::ArgMatches;
+use memory_admin_simulator::{
+    BeladyAnalysis, Comparison, CsvSink, Instruction, JsonlSink, MissRatioCurve, PolicyRegistry,
+    SimulationError, Sweep, System, TextSink,
+};
+
 mod cli;
-mod instruction;
-mod process;
-mod system;
-mod time;
-mod util;
 
-use algorithm::PageReplacementAlgorithm;
-use instruction::Instruction;
-use system::System;
+use cli::{OutputFormat, SizeArgument};
 
 /// Punto de entrada del programa
 fn main() {
+    // Se obtiene el registro con las políticas de reemplazo disponibles
+    let registry = PolicyRegistry::default();
     // clap parsea los argumentos de la consola y los guarda en un objeto de coincidencias
-    let matches = cli::get_app().get_matches();
-    // Se obtiene el algoritmo
-    let algorithm = PageReplacementAlgorithm::from_matches(&matches);
-    // Se obtiene el nombre del archivo
-    let filename = cli::get_filename(&matches);
-    // Se abre el archivo y se lee
-    let file = fs::read_to_string(filename)
-        .expect(format!("No se encontró el archivo {}", filename).as_ref());
-    // Se obtiene el tamaño de página
-    let page_size = cli::get_size(&matches, cli::SizeArgument::Page);
-    // Se obtiene el tamaño de la memoria real
-    let real_memory_size = cli::get_size(&matches, cli::SizeArgument::RealMemory);
-    // Se obtiene el tamaño del espacio swap
-    let swap_space_size = cli::get_size(&matches, cli::SizeArgument::SwapSpace);
-    // Se instancía el sistema pasándole el algoritmo, el tamaño de página, de memoria real y de
-    // espacio swap
-    let mut system = System::new(algorithm, page_size, real_memory_size, swap_space_size);
-
-    // Por cada línea del archivo
-    file.lines()
-        // Se intenta convertir la línea en una instrucción
-        .map(|line| Instruction::try_from(line))
-        // Por cada posible instrucción
-        .for_each(|maybe_ins| {
-            match maybe_ins {
-                // Si la instrucción se pudo parsear se manda a que el sistema la ejecute
-                Ok(ins) => {
+    let matches = cli::get_app(&registry).get_matches();
+    // Dependiendo del subcomando se comparan las políticas o se ejecuta una simulación
+    match matches.subcommand() {
+        ("compare", Some(compare_matches)) => compare(&registry, compare_matches),
+        ("sweep", Some(sweep_matches)) => sweep(&registry, sweep_matches),
+        ("belady", Some(belady_matches)) => belady(&registry, belady_matches),
+        ("mrc", Some(mrc_matches)) => mrc(mrc_matches),
+        _ => simulate(&registry, &matches),
+    }
+}
+
+/// Abre el archivo de instrucciones y lo lee
+fn read_file(filename: &str) -> String {
+    fs::read_to_string(filename)
+        .unwrap_or_else(|_| panic!("No se encontró el archivo {}", filename))
+}
+
+/// Abre el archivo de instrucciones y regresa la lista de instrucciones válidas. Las líneas que
+/// no se pudieron analizar se ignoran
+fn read_trace(filename: &str) -> Vec<Instruction> {
+    read_file(filename)
+        .lines()
+        .filter_map(|line| Instruction::try_from(line).ok())
+        .collect()
+}
+
+/// Imprime el error y termina el programa
+fn exit_with_error(error: SimulationError) -> ! {
+    eprintln!("Error: {}", error);
+    std::process::exit(1);
+}
+
+/// Ejecuta las instrucciones del archivo con cada una de las políticas elegidas e imprime una
+/// tabla comparando sus resultados
+fn compare(registry: &PolicyRegistry, matches: &ArgMatches) {
+    let trace = read_trace(cli::get_filename(matches));
+    let comparison = Comparison::run(
+        registry,
+        &cli::get_algorithms(matches, registry),
+        &cli::get_policy_options(matches),
+        &cli::get_system_options(matches),
+        &trace,
+    )
+    .unwrap_or_else(|error| exit_with_error(error));
+    print!("{}", comparison);
+}
+
+/// Ejecuta las instrucciones del archivo con cada combinación de los tamaños elegidos y cada una
+/// de las políticas elegidas, e imprime una tabla con la tasa de fallos y el turnaround promedio
+fn sweep(registry: &PolicyRegistry, matches: &ArgMatches) {
+    let trace = read_trace(cli::get_filename(matches));
+    let sweep = Sweep::run(
+        registry,
+        &cli::get_algorithms(matches, registry),
+        &cli::get_policy_options(matches),
+        &cli::get_system_options(matches),
+        (
+            &cli::get_size_list(matches, SizeArgument::Page),
+            &cli::get_size_list(matches, SizeArgument::RealMemory),
+            &cli::get_size_list(matches, SizeArgument::SwapSpace),
+        ),
+        &trace,
+    )
+    .unwrap_or_else(|error| exit_with_error(error));
+    print!("{}", sweep);
+}
+
+/// Ejecuta las instrucciones del archivo con un número creciente de marcos e imprime los casos
+/// en los que agregar marcos aumentó los fallos de página
+fn belady(registry: &PolicyRegistry, matches: &ArgMatches) {
+    let trace = read_trace(cli::get_filename(matches));
+    let analysis = BeladyAnalysis::run(
+        registry,
+        cli::get_algorithm(matches),
+        &cli::get_policy_options(matches),
+        &cli::get_system_options(matches),
+        &cli::get_frame_counts(matches),
+        &trace,
+    )
+    .unwrap_or_else(|error| exit_with_error(error));
+    print!("{}", analysis);
+}
+
+/// Calcula la curva de tasa de fallos de LRU de las instrucciones del archivo y la imprime como
+/// tabla, y si se pidió la escribe como gráfica en un archivo SVG
+fn mrc(matches: &ArgMatches) {
+    let trace = read_trace(cli::get_filename(matches));
+    let curve = MissRatioCurve::from_trace(&trace, cli::get_size(matches, SizeArgument::Page));
+    print!("{}", curve);
+    if let Some(path) = cli::get_svg(matches) {
+        if let Err(error) = fs::write(path, curve.to_svg()) {
+            eprintln!("Error al escribir el archivo {}: {}", path, error);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Ejecuta las instrucciones del archivo con la política elegida, imprimiendo el resultado de
+/// cada instrucción
+fn simulate(registry: &PolicyRegistry, matches: &ArgMatches) {
+    // Se obtiene la política de reemplazo. Si no hay una registrada con ese nombre se imprime el
+    // error y se termina el programa
+    let policy = registry
+        .create(
+            cli::get_algorithm(matches),
+            &cli::get_policy_options(matches),
+        )
+        .unwrap_or_else(|error| exit_with_error(error));
+    // Se obtiene el nombre del archivo, se abre y se lee
+    let file = read_file(cli::get_filename(matches));
+    // Se obtienen las opciones del sistema: el tamaño de página, de memoria real, de espacio swap
+    // y si se usa paginación por demanda
+    let options = cli::get_system_options(matches);
+    // Se instancía el sistema pasándole la política y las opciones
+    let mut system = System::new(policy, &options);
+    // Los eventos del sistema se imprimen en la consola en el formato elegido
+    let format = cli::get_format(matches);
+    match format {
+        OutputFormat::Text => system.add_sink(Box::new(TextSink::new())),
+        OutputFormat::Jsonl => system.add_sink(Box::new(JsonlSink::new())),
+    }
+    // Si se pidió, las estadísticas de los procesos también se escriben en un archivo CSV
+    if let Some(path) = cli::get_stats_csv(matches) {
+        system.add_sink(Box::new(CsvSink::new(path)));
+    }
+
+    // Se convierte cada línea del archivo en una posible instrucción antes de ejecutar cualquiera
+    let maybe_instructions: Vec<_> = file.lines().map(Instruction::try_from).collect();
+    // Se le pasa al sistema la lista de instrucciones válidas para las políticas que necesitan
+    // conocer los accesos futuros
+    let trace: Vec<Instruction> = maybe_instructions
+        .iter()
+        .filter_map(|maybe_ins| maybe_ins.as_ref().ok().cloned())
+        .collect();
+    system.prepare(&trace);
+
+    // Por cada posible instrucción
+    for maybe_ins in maybe_instructions {
+        match (maybe_ins, format) {
+            // Si la instrucción se pudo parsear se manda a que el sistema la ejecute
+            (Ok(ins), OutputFormat::Text) => {
+                println!("{}", ins);
+                // El sistema imprime el error si la instrucción no se pudo ejecutar
+                let _ = system.process_instruction(&ins);
+                println!();
+            }
+            (Ok(ins), OutputFormat::Jsonl) => {
+                let _ = system.process_instruction(&ins);
+            }
+            // En otro caso se imprime un error
+            (Err((ins, error)), OutputFormat::Text) => {
+                if !ins.is_empty() {
                     println!("{}", ins);
-                    system.process_instruction(&ins);
-                }
-                // En otro caso se imprime un error
-                Err((ins, error)) => {
-                    if ins.len() > 0 {
-                        println!("{}", ins);
-                    }
-                    println!("Error al analizar instrucción: {}", error);
                 }
+                println!("Error al analizar instrucción: {}", error);
+                println!();
+            }
+            (Err((ins, error)), OutputFormat::Jsonl) => {
+                println!(
+                    "{}",
+                    serde_json::json!({ "line": ins, "parse_error": error }),
+                );
             }
-            println!();
-        });
+        }
+        // Después de una instrucción E se ignora el resto del archivo
+        if system.is_finished() {
+            break;
+        }
+    }
 }
@@ -0,0 +1,287 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+
+use crate::instruction::Instruction;
+use crate::process::PID;
+use crate::util;
+
+/// Árbol de Fenwick (binary indexed tree) que guarda un contador por posición y calcula sumas
+/// de prefijos en tiempo logarítmico
+#[derive(Debug)]
+struct FenwickTree {
+    tree: Vec<i64>,
+}
+
+impl FenwickTree {
+    /// Constructor de un árbol con `size` posiciones en cero
+    fn new(size: usize) -> Self {
+        FenwickTree {
+            tree: vec![0; size + 1],
+        }
+    }
+
+    /// Le suma `value` al contador de la posición `index`
+    fn add(&mut self, index: usize, value: i64) {
+        let mut i = index + 1;
+        while i < self.tree.len() {
+            self.tree[i] += value;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// Regresa la suma de los contadores de las posiciones menores a `end`
+    fn prefix_sum(&self, end: usize) -> i64 {
+        let mut sum = 0;
+        let mut i = end;
+        while i > 0 {
+            sum += self.tree[i];
+            i -= i & i.wrapping_neg();
+        }
+        sum
+    }
+
+    /// Regresa la suma de los contadores de las posiciones desde `start` hasta antes de `end`
+    fn range_sum(&self, start: usize, end: usize) -> i64 {
+        self.prefix_sum(end) - self.prefix_sum(start)
+    }
+}
+
+/// Curva de tasa de fallos (miss-ratio curve) de la política LRU con paginación por demanda,
+/// calculada con el análisis de distancias de pila de Mattson: la distancia de un acceso es el
+/// número de páginas distintas a las que se accedió desde el acceso anterior a la misma página, y
+/// un acceso es un fallo en una memoria de C marcos si y sólo si su distancia es mayor o igual
+/// a C. Todas las páginas de todos los procesos comparten la memoria real.
+/// Las páginas de un proceso liberado con L dejan un hueco en la pila, que representa un marco
+/// libre en las memorias en las que la página estaba cargada. Cuando una página sube a la cima de
+/// la pila, el hueco más cercano a la cima que esté arriba de ella baja a su lugar anterior (y si
+/// la página es nueva el hueco desaparece), porque en las memorias que fallan la página ocupa el
+/// marco libre en vez de reemplazar a otra. Así la distancia sigue siendo exacta con cualquier
+/// número de marcos. La instrucción F vacía la memoria, por lo que también vacía la pila.
+/// Guarda:
+/// - page_size: tamaño en bytes de una página
+/// - accesses: número de accesos válidos
+/// - compulsory_misses: número de primeros accesos a una página, que son fallos con cualquier
+///   número de marcos
+/// - distances: número de accesos con cada distancia de pila
+#[derive(Debug, Clone)]
+pub struct MissRatioCurve {
+    page_size: usize,
+    accesses: usize,
+    compulsory_misses: usize,
+    distances: Vec<usize>,
+}
+
+impl MissRatioCurve {
+    /// Calcula la curva recorriendo una sola vez la lista de instrucciones. Se ignoran las
+    /// instrucciones que el sistema rechazaría por el pid o por la dirección
+    pub fn from_trace(trace: &[Instruction], page_size: usize) -> Self {
+        let num_accesses = trace
+            .iter()
+            .filter(|instruction| matches!(instruction, Instruction::Access { .. }))
+            .count();
+        // Cada acceso ocupa una posición en el árbol, y sólo el último acceso a cada página (o el
+        // hueco que la reemplazó) tiene un 1, por lo que la suma entre dos posiciones es la
+        // distancia en la pila
+        let mut tree = FenwickTree::new(num_accesses);
+        let mut last_access = HashMap::<(PID, usize), usize>::new();
+        let mut holes = BTreeSet::<usize>::new();
+        let mut sizes = HashMap::<PID, usize>::new();
+        let mut pages = HashMap::<PID, HashSet<usize>>::new();
+        let mut curve = MissRatioCurve {
+            page_size,
+            accesses: 0,
+            compulsory_misses: 0,
+            distances: Vec::new(),
+        };
+        for instruction in trace {
+            match instruction {
+                Instruction::Process { pid, size, .. } => {
+                    sizes.entry(*pid).or_insert(*size);
+                }
+                Instruction::Access { pid, address, .. } => {
+                    match sizes.get(pid) {
+                        Some(size) if address < size => {}
+                        _ => continue,
+                    }
+                    let page = (*pid, address / page_size);
+                    let position = curve.accesses;
+                    match last_access.insert(page, position) {
+                        Some(previous) => {
+                            let distance = tree.range_sum(previous + 1, position) as usize;
+                            if curve.distances.len() <= distance {
+                                curve.distances.resize(distance + 1, 0);
+                            }
+                            curve.distances[distance] += 1;
+                            // El hueco más cercano a la cima baja al lugar que deja la página
+                            match holes.range(previous + 1..).next_back().copied() {
+                                Some(hole) => {
+                                    holes.remove(&hole);
+                                    tree.add(hole, -1);
+                                    holes.insert(previous);
+                                }
+                                None => tree.add(previous, -1),
+                            }
+                        }
+                        None => {
+                            curve.compulsory_misses += 1;
+                            pages.entry(*pid).or_default().insert(page.1);
+                            // La página nueva ocupa el hueco más cercano a la cima
+                            if let Some(hole) = holes.iter().next_back().copied() {
+                                holes.remove(&hole);
+                                tree.add(hole, -1);
+                            }
+                        }
+                    }
+                    tree.add(position, 1);
+                    curve.accesses += 1;
+                }
+                // Las páginas del proceso liberado se vuelven huecos en la pila
+                Instruction::Free { pid } => {
+                    sizes.remove(pid);
+                    for page in pages.remove(pid).unwrap_or_default() {
+                        if let Some(position) = last_access.remove(&(*pid, page)) {
+                            holes.insert(position);
+                        }
+                    }
+                }
+                // La instrucción F reinicia el sistema, vaciando la pila
+                Instruction::End() => {
+                    for position in last_access.drain().map(|(_, position)| position) {
+                        tree.add(position, -1);
+                    }
+                    for position in std::mem::take(&mut holes) {
+                        tree.add(position, -1);
+                    }
+                    sizes.clear();
+                    pages.clear();
+                }
+                // La simulación termina con la instrucción E
+                Instruction::Exit() => break,
+                Instruction::Comment(_) => {}
+            }
+        }
+        curve
+    }
+
+    /// Get para el número de accesos válidos
+    pub fn get_accesses(&self) -> usize {
+        self.accesses
+    }
+
+    /// Regresa el número de marcos a partir del cual la curva ya no baja: con ese número de
+    /// marcos sólo fallan los primeros accesos a cada página
+    pub fn max_frames(&self) -> usize {
+        self.distances.len().max(1)
+    }
+
+    /// Regresa el número de fallos de página con una memoria real de `frames` marcos
+    pub fn misses(&self, frames: usize) -> usize {
+        self.compulsory_misses + self.distances.iter().skip(frames).sum::<usize>()
+    }
+
+    /// Regresa la proporción de accesos que son fallos de página con una memoria real de
+    /// `frames` marcos (0 si no hubo accesos)
+    pub fn miss_ratio(&self, frames: usize) -> f64 {
+        if self.accesses == 0 {
+            0.0
+        } else {
+            self.misses(frames) as f64 / self.accesses as f64
+        }
+    }
+
+    /// Regresa una gráfica de la curva en formato SVG, con el número de marcos en el eje x y la
+    /// tasa de fallos en el eje y
+    pub fn to_svg(&self) -> String {
+        const WIDTH: f64 = 640.0;
+        const HEIGHT: f64 = 400.0;
+        const MARGIN: f64 = 50.0;
+        let max_frames = self.max_frames();
+        let plot_width = WIDTH - 2.0 * MARGIN;
+        let plot_height = HEIGHT - 2.0 * MARGIN;
+        // Convierte un número de marcos y una tasa de fallos en coordenadas de la imagen
+        let x = |frames: usize| MARGIN + plot_width * frames as f64 / max_frames as f64;
+        let y = |ratio: f64| HEIGHT - MARGIN - plot_height * ratio;
+        let points = (1..=max_frames)
+            .map(|frames| format!("{:.1},{:.1}", x(frames), y(self.miss_ratio(frames))))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+            w = WIDTH,
+            h = HEIGHT,
+        );
+        svg += &format!(
+            "<rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n",
+            WIDTH, HEIGHT,
+        );
+        // Los ejes
+        svg += &format!(
+            "<polyline points=\"{x0},{y0} {x0},{y1} {x1},{y1}\" fill=\"none\" stroke=\"black\"/>\n",
+            x0 = MARGIN,
+            y0 = MARGIN,
+            x1 = WIDTH - MARGIN,
+            y1 = HEIGHT - MARGIN,
+        );
+        svg += &format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"14\">Marcos ({} bytes por marco)</text>\n",
+            WIDTH / 2.0,
+            HEIGHT - 10.0,
+            self.page_size,
+        );
+        svg += &format!(
+            "<text x=\"15\" y=\"{}\" text-anchor=\"middle\" font-size=\"14\" transform=\"rotate(-90 15 {})\">Tasa de fallos</text>\n",
+            HEIGHT / 2.0,
+            HEIGHT / 2.0,
+        );
+        // Las marcas de los ejes
+        for (ratio, label) in &[(0.0, "0%"), (0.5, "50%"), (1.0, "100%")] {
+            svg += &format!(
+                "<text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\" font-size=\"12\">{}</text>\n",
+                MARGIN - 5.0,
+                y(*ratio) + 4.0,
+                label,
+            );
+        }
+        for frames in &[1, max_frames] {
+            svg += &format!(
+                "<text x=\"{:.1}\" y=\"{}\" text-anchor=\"middle\" font-size=\"12\">{}</text>\n",
+                x(*frames),
+                HEIGHT - MARGIN + 16.0,
+                frames,
+            );
+        }
+        svg += &format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"steelblue\" stroke-width=\"2\"/>\n",
+            points,
+        );
+        svg += "</svg>\n";
+        svg
+    }
+}
+
+/// Se imprime una tabla con los fallos de página y la tasa de fallos de cada número de marcos,
+/// hasta el número de marcos a partir del cual la curva ya no baja
+impl fmt::Display for MissRatioCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rows = vec![vec![
+            String::from("Marcos"),
+            String::from("Memoria real"),
+            String::from("Fallos"),
+            String::from("Tasa de fallos"),
+        ]];
+        for frames in 1..=self.max_frames() {
+            rows.push(vec![
+                frames.to_string(),
+                format!("{} bytes", frames * self.page_size),
+                self.misses(frames).to_string(),
+                format!("{:.2}%", self.miss_ratio(frames) * 100.0),
+            ]);
+        }
+        write!(f, "{}", util::display_table(&rows))?;
+        writeln!(
+            f,
+            "{} accesos, {} fallos obligatorios (primer acceso a una página)",
+            self.accesses, self.compulsory_misses,
+        )
+    }
+}
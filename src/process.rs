@@ -1,5 +1,6 @@
 use std::ops::Range;
 
+use crate::system::{Frame, Memory};
 use crate::time::Time;
 use crate::util;
 
@@ -7,30 +8,72 @@ use crate::util;
 /// en caso de ser necesario
 pub type PID = u16;
 
+/// Prioridad que tiene un proceso cuando la instrucción P no la especifica
+pub const DEFAULT_PRIORITY: u16 = 1;
+
 /// Guarda la información de un proceso:
 /// - pid: número que identifica el proceso
 /// - size: tamaño del proceso en bytes
+/// - priority: prioridad del proceso, un entero positivo. Entre mayor sea, menos probable es que
+///   el OOM killer lo elija para terminarlo
+/// - killed: si el OOM killer terminó el proceso para liberar memoria
+/// - quota: número de marcos de la memoria real que le tocan al proceso, o None si el sistema no
+///   reparte los marcos en cuotas. Si el proceso ya se liberó es la cuota que tenía al liberarse
 /// - life: rango de tiempo del sistema desde que las páginas del proceso terminaron de cargarse hasta que las páginas del proceso terminaron de liberarse
 /// - swap_ins: número de veces en las que ha sido necesario que una página del proceso se mueva hacia la memoria real del sistema
 /// - swap_outs: número de veces en las que ha sido necesario que una página del proceso se mueva hacia el espacio swap del sistema
+/// - write_backs: número de swap-outs en los que fue necesario escribir la página en el espacio swap (porque se modificó o no tenía una copia ahí)
+/// - hits: número de accesos a páginas que ya estaban en la memoria real
+/// - page_faults: número de accesos a páginas que no estaban en la memoria real
+/// - compulsory_faults: número de fallos de página de páginas que nunca habían estado en la
+///   memoria real. El resto de los fallos son de capacidad (la página ya había estado en la
+///   memoria real pero se reemplazó). No hay fallos de conflicto porque cualquier página se puede
+///   cargar en cualquier marco
+/// - loaded_pages: guarda para cada índice de página si ya estuvo en la memoria real, ya sea
+///   porque la cargó la instrucción P o porque se accedió a ella
+/// - page_table: tabla de páginas, guarda para cada índice de página el marco en el que se
+///   encuentra (o None si la página todavía no se carga con paginación por demanda)
+/// - resident_pages: número de páginas del proceso que están en la memoria real. Se actualiza
+///   junto con la tabla de páginas para no recorrerla cada vez que se necesita
 #[derive(Debug)]
 pub struct Process {
     pid: PID,
     size: usize,
+    priority: u16,
+    killed: bool,
+    quota: Option<usize>,
     life: Range<Time>,
-    swap_ins: u16,
-    swap_outs: u16,
+    swap_ins: usize,
+    swap_outs: usize,
+    write_backs: usize,
+    hits: usize,
+    page_faults: usize,
+    compulsory_faults: usize,
+    loaded_pages: Vec<bool>,
+    page_table: Vec<Option<Frame>>,
+    resident_pages: usize,
 }
 
 impl Process {
-    /// Constructor al que se le pasa el pid y el tamaño en bytes
-    pub fn new(pid: PID, size: usize) -> Self {
+    /// Constructor al que se le pasa el pid, el tamaño en bytes, la prioridad y el tamaño de
+    /// página del sistema para crear la tabla de páginas
+    pub fn new(pid: PID, size: usize, priority: u16, page_size: usize) -> Self {
         Process {
             pid,
             size,
+            priority,
+            killed: false,
+            quota: None,
             life: (Time::new()..Time::max()),
             swap_ins: 0,
             swap_outs: 0,
+            write_backs: 0,
+            hits: 0,
+            page_faults: 0,
+            compulsory_faults: 0,
+            loaded_pages: vec![false; util::ceil_div(size, page_size)],
+            page_table: vec![None; util::ceil_div(size, page_size)],
+            resident_pages: 0,
         }
     }
 
@@ -39,6 +82,41 @@ impl Process {
         self.pid
     }
 
+    /// Get para el tamaño en bytes del proceso
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    /// Get para la prioridad del proceso
+    pub fn get_priority(&self) -> u16 {
+        self.priority
+    }
+
+    /// Get para saber si el OOM killer terminó el proceso
+    pub fn is_killed(&self) -> bool {
+        self.killed
+    }
+
+    /// Marca el proceso como terminado por el OOM killer
+    pub fn set_killed(&mut self) {
+        self.killed = true;
+    }
+
+    /// Get para la cuota de marcos del proceso
+    pub fn get_quota(&self) -> Option<usize> {
+        self.quota
+    }
+
+    /// Set para la cuota de marcos del proceso
+    pub fn set_quota(&mut self, quota: Option<usize>) {
+        self.quota = quota;
+    }
+
+    /// Get para una referencia al rango de vida del proceso
+    pub fn get_life(&self) -> &Range<Time> {
+        &self.life
+    }
+
     /// Calcula el número de páginas dependiendo del tamaño de la página
     pub fn num_pages(&self, page_size: usize) -> usize {
         util::ceil_div(self.size, page_size)
@@ -49,6 +127,43 @@ impl Process {
         address < self.size
     }
 
+    /// Regresa el marco en el que se encuentra la página con el índice proporcionado
+    pub fn get_page_frame(&self, page_index: usize) -> Option<Frame> {
+        self.page_table[page_index]
+    }
+
+    /// Actualiza el marco en el que se encuentra la página con el índice proporcionado y la cuenta
+    /// de páginas en la memoria real
+    pub fn set_page_frame(&mut self, page_index: usize, frame: Option<Frame>) {
+        let is_resident =
+            |frame: Option<Frame>| frame.is_some_and(|frame| frame.get_memory() == Memory::Real);
+        if is_resident(self.page_table[page_index]) {
+            self.resident_pages -= 1;
+        }
+        if is_resident(frame) {
+            self.resident_pages += 1;
+            self.loaded_pages[page_index] = true;
+        }
+        self.page_table[page_index] = frame;
+    }
+
+    /// Regresa el número de páginas del proceso que están en la memoria real
+    pub fn count_resident_pages(&self) -> usize {
+        self.resident_pages
+    }
+
+    /// Get para una referencia a la tabla de páginas
+    pub fn get_page_table(&self) -> &[Option<Frame>] {
+        &self.page_table
+    }
+
+    /// Borra la tabla de páginas, cuando el proceso ya se liberó
+    pub fn clear_page_table(&mut self) {
+        self.page_table = Vec::new();
+        self.loaded_pages = Vec::new();
+        self.resident_pages = 0;
+    }
+
     /// Añade uno al contador de swap-ins
     pub fn add_swap_in(&mut self) {
         self.swap_ins += 1;
@@ -59,8 +174,51 @@ impl Process {
         self.swap_outs += 1;
     }
 
+    /// Añade uno al contador de write-backs
+    pub fn add_write_back(&mut self) {
+        self.write_backs += 1;
+    }
+
+    /// Get para el número de write-backs
+    pub fn get_write_backs(&self) -> usize {
+        self.write_backs
+    }
+
+    /// Añade uno al contador de hits
+    pub fn add_hit(&mut self) {
+        self.hits += 1;
+    }
+
+    /// Get para el número de hits
+    pub fn get_hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Añade uno al contador de fallos de página, y al de fallos obligatorios si la página nunca
+    /// había estado en la memoria real. Se llama antes de actualizar la tabla de páginas con el
+    /// marco en el que se carga la página
+    pub fn add_page_fault(&mut self, page_index: usize) {
+        self.page_faults += 1;
+        if !self.loaded_pages[page_index] {
+            self.compulsory_faults += 1;
+        }
+    }
+
+    /// Get para el número de fallos de página
+    pub fn get_page_faults(&self) -> usize {
+        self.page_faults
+    }
+
+    /// Regresa un tuple formado por el número de fallos de página obligatorios y de capacidad
+    pub fn get_fault_kinds(&self) -> (usize, usize) {
+        (
+            self.compulsory_faults,
+            self.page_faults - self.compulsory_faults,
+        )
+    }
+
     /// Regresa un tuple formado por el número de swap-ins y swap-outs
-    pub fn get_swaps(&self) -> (u16, u16) {
+    pub fn get_swaps(&self) -> (usize, usize) {
         (self.swap_ins, self.swap_outs)
     }
 
@@ -90,13 +248,20 @@ impl Process {
 /// - pid: número que identifica al proceso que pertenece
 /// - index: índice de la página dentro de la memoria virtual del proceso
 /// - created: tiempo del sistema en el que se creó la página
-/// - accessed: tiempo del sistema la última vez que se accedió a la página
-#[derive(Debug)]
+/// - referenced: bit de referencia, se prende cada vez que se accede a la página y las políticas
+///   de reemplazo pueden apagarlo
+/// - dirty: bit de modificación, se prende cuando se modifica la página y se apaga cuando se
+///   escribe en el espacio swap
+/// - swap_copy: índice del marco del espacio swap que guarda una copia de la página, si la
+///   página está en memoria real y tiene una copia
+#[derive(Debug, Clone)]
 pub struct ProcessPage {
     pid: PID,
     index: usize,
     created: Time,
-    accessed: Time,
+    referenced: bool,
+    dirty: bool,
+    swap_copy: Option<usize>,
 }
 
 impl ProcessPage {
@@ -107,15 +272,12 @@ impl ProcessPage {
             pid,
             index,
             created,
-            accessed: created,
+            referenced: false,
+            dirty: false,
+            swap_copy: None,
         }
     }
 
-    /// Get para el pid de la página
-    pub fn get_pid(&self) -> PID {
-        self.pid
-    }
-
     /// Regresa un tuple formado por el pid y el índice de la página
     pub fn get_page_info(&self) -> (PID, usize) {
         (self.pid, self.index)
@@ -126,13 +288,33 @@ impl ProcessPage {
         &self.created
     }
 
-    /// Get para una referencia al tiempo de acceso del proceso
-    pub fn get_accessed_time(&self) -> &Time {
-        &self.accessed
+    /// Get para el bit de referencia de la página
+    pub fn is_referenced(&self) -> bool {
+        self.referenced
+    }
+
+    /// Set para el bit de referencia de la página
+    pub fn set_referenced(&mut self, referenced: bool) {
+        self.referenced = referenced;
+    }
+
+    /// Get para el bit de modificación de la página
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Set para el bit de modificación de la página
+    pub fn set_dirty(&mut self, dirty: bool) {
+        self.dirty = dirty;
+    }
+
+    /// Get para el índice del marco del espacio swap con la copia de la página
+    pub fn get_swap_copy(&self) -> Option<usize> {
+        self.swap_copy
     }
 
-    /// Set para el tiempo de creación del proceso
-    pub fn update_accessed_time(&mut self, accessed: Time) {
-        self.accessed = accessed;
+    /// Set para el índice del marco del espacio swap con la copia de la página
+    pub fn set_swap_copy(&mut self, swap_copy: Option<usize>) {
+        self.swap_copy = swap_copy;
     }
 }
@@ -0,0 +1,198 @@
+use std::cell::Cell;
+use std::fmt;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::algorithm::{PolicyOptions, PolicyRegistry};
+use crate::error::SimulationError;
+use crate::event::SimulationEvent;
+use crate::instruction::Instruction;
+use crate::system::{System, SystemOptions};
+use crate::util;
+
+/// Resultado de ejecutar la lista de instrucciones con una combinación de tamaños y una política:
+/// - page_size, real_memory_size, swap_space_size: los tamaños en bytes de la combinación
+/// - algorithm: el nombre de la política de reemplazo
+/// - accesses: número de accesos (instrucciones A) que se ejecutaron sin error
+/// - page_faults: número de accesos a páginas que no estaban en la memoria real
+/// - average_turnaround: turnaround promedio en segundos de los procesos liberados en todos los
+///   escenarios
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepPoint {
+    pub page_size: usize,
+    pub real_memory_size: usize,
+    pub swap_space_size: usize,
+    pub algorithm: String,
+    pub accesses: usize,
+    pub page_faults: usize,
+    pub average_turnaround: f64,
+}
+
+impl SweepPoint {
+    /// Regresa la proporción de accesos que provocaron un fallo de página (None si no hubo
+    /// accesos)
+    pub fn fault_rate(&self) -> Option<f64> {
+        if self.accesses == 0 {
+            None
+        } else {
+            Some(self.page_faults as f64 / self.accesses as f64)
+        }
+    }
+}
+
+/// Guarda los resultados de un barrido de parámetros:
+/// - algorithms: los nombres de las políticas en el orden en el que se pidieron
+/// - points: un resultado por cada combinación de tamaños y política, ordenados por tamaño de
+///   página, de memoria real, de espacio swap y por política
+#[derive(Debug, Clone)]
+pub struct Sweep {
+    pub algorithms: Vec<String>,
+    pub points: Vec<SweepPoint>,
+}
+
+impl Sweep {
+    /// Ejecuta la lista de instrucciones con cada combinación de los tamaños de página, de
+    /// memoria real y de espacio swap proporcionados y con cada una de las políticas, repartiendo
+    /// las combinaciones entre los núcleos del procesador. El resto de las opciones del sistema
+    /// se toman de `system_options`.
+    /// Regresa un error si alguno de los nombres no está en el registro
+    pub fn run(
+        registry: &PolicyRegistry,
+        names: &[&str],
+        policy_options: &PolicyOptions,
+        system_options: &SystemOptions,
+        sizes: (&[usize], &[usize], &[usize]),
+        trace: &[Instruction],
+    ) -> Result<Self, SimulationError> {
+        // Revisamos que todas las políticas existan antes de empezar
+        for name in names {
+            registry.create(name, policy_options)?;
+        }
+        let (page_sizes, real_memory_sizes, swap_space_sizes) = sizes;
+        let mut jobs = Vec::new();
+        for &page_size in page_sizes {
+            for &real_memory_size in real_memory_sizes {
+                for &swap_space_size in swap_space_sizes {
+                    for name in names {
+                        let options = SystemOptions {
+                            page_size,
+                            real_memory_size,
+                            swap_space_size,
+                            ..system_options.clone()
+                        };
+                        jobs.push((options, *name));
+                    }
+                }
+            }
+        }
+        // Cada hilo toma el siguiente trabajo sin hacer hasta que se acaben, y guarda su
+        // resultado en la misma posición que el trabajo
+        let next_job = AtomicUsize::new(0);
+        let results = Mutex::new(vec![None; jobs.len()]);
+        let num_threads = thread::available_parallelism()
+            .map(usize::from)
+            .unwrap_or(1)
+            .min(jobs.len());
+        thread::scope(|scope| {
+            for _ in 0..num_threads {
+                scope.spawn(|| loop {
+                    let index = next_job.fetch_add(1, Ordering::Relaxed);
+                    let (options, name) = match jobs.get(index) {
+                        Some(job) => job,
+                        None => break,
+                    };
+                    // La política ya se revisó, por lo que no puede fallar
+                    let policy = registry.create(name, policy_options).unwrap();
+                    let point = run_point(System::new(policy, options), options, name, trace);
+                    results.lock().unwrap()[index] = Some(point);
+                });
+            }
+        });
+        Ok(Sweep {
+            algorithms: names.iter().map(|name| name.to_string()).collect(),
+            points: results
+                .into_inner()
+                .unwrap()
+                .into_iter()
+                .flatten()
+                .collect(),
+        })
+    }
+}
+
+/// Ejecuta la lista de instrucciones en el sistema proporcionado, contando los accesos y los
+/// fallos de página con los eventos del sistema
+fn run_point(
+    mut system: System,
+    options: &SystemOptions,
+    name: &str,
+    trace: &[Instruction],
+) -> SweepPoint {
+    let accesses = Rc::new(Cell::new(0));
+    let page_faults = Rc::new(Cell::new(0));
+    {
+        let accesses = Rc::clone(&accesses);
+        let page_faults = Rc::clone(&page_faults);
+        system.add_sink(Box::new(move |event: &SimulationEvent| match event {
+            SimulationEvent::PageAccessed { .. } => accesses.set(accesses.get() + 1),
+            SimulationEvent::PageFault { .. } => page_faults.set(page_faults.get() + 1),
+            _ => {}
+        }));
+    }
+    let reports = system.run_scenarios(trace);
+    // Promediamos el turnaround de los procesos de todos los escenarios
+    let turnarounds: Vec<f64> = reports
+        .iter()
+        .flat_map(|report| &report.processes)
+        .map(|process| f64::from(process.turnaround) / 1000.0)
+        .collect();
+    SweepPoint {
+        page_size: options.page_size,
+        real_memory_size: options.real_memory_size,
+        swap_space_size: options.swap_space_size,
+        algorithm: name.to_string(),
+        accesses: accesses.get(),
+        page_faults: page_faults.get(),
+        average_turnaround: turnarounds.iter().sum::<f64>() / turnarounds.len() as f64,
+    }
+}
+
+/// Se imprime una tabla con un renglón por combinación de tamaños y, por cada política, las
+/// columnas de tasa de fallos de página y turnaround promedio
+impl fmt::Display for Sweep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = vec![String::new(); 3];
+        let mut headers = vec![
+            String::from("Página"),
+            String::from("Memoria real"),
+            String::from("Swap"),
+        ];
+        for name in &self.algorithms {
+            names.extend(vec![name.clone(), String::new()]);
+            headers.extend(vec![
+                String::from("tasa de fallos"),
+                String::from("turnaround prom."),
+            ]);
+        }
+        let mut rows = vec![names, headers];
+        // Los resultados de una combinación son consecutivos, uno por política
+        for chunk in self.points.chunks(self.algorithms.len().max(1)) {
+            let mut row = vec![
+                chunk[0].page_size.to_string(),
+                chunk[0].real_memory_size.to_string(),
+                chunk[0].swap_space_size.to_string(),
+            ];
+            for point in chunk {
+                row.push(match point.fault_rate() {
+                    Some(rate) => format!("{:.2}%", rate * 100.0),
+                    None => String::from("-"),
+                });
+                row.push(format!("{:.2}s", point.average_turnaround));
+            }
+            rows.push(row);
+        }
+        write!(f, "{}", util::display_table(&rows))
+    }
+}
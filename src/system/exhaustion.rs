@@ -0,0 +1,124 @@
+use super::System;
+use crate::error::SimulationError;
+use crate::event::SimulationEvent;
+use crate::process::{Process, PID};
+use crate::time::Time;
+use crate::util;
+
+/// Usamos este enum para elegir qué hace el sistema cuando no hay marcos libres suficientes en
+/// las memorias (se agotó el espacio swap) para un proceso nuevo o para cargar una página:
+/// - Reject: la instrucción falla con un error y el sistema se queda como estaba
+/// - Kill: el OOM killer termina otros procesos, elegidos con la heurística del sistema, hasta
+///   que haya espacio
+/// - Block: la instrucción P se bloquea hasta que una instrucción L libere espacio suficiente.
+///   Un acceso no se puede bloquear, por lo que se rechaza
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SwapExhaustionResponse {
+    #[default]
+    Reject,
+    Kill,
+    Block,
+}
+
+impl SwapExhaustionResponse {
+    /// Lista de todas las variantes del enum
+    pub const ALL: [SwapExhaustionResponse; 3] = [
+        SwapExhaustionResponse::Reject,
+        SwapExhaustionResponse::Kill,
+        SwapExhaustionResponse::Block,
+    ];
+
+    /// Esta función se aplica a una variante del enum y regresa un string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SwapExhaustionResponse::Reject => "reject",
+            SwapExhaustionResponse::Kill => "kill",
+            SwapExhaustionResponse::Block => "block",
+        }
+    }
+}
+
+impl System {
+    /// Calcula el número de bytes que ocupan las páginas de un proceso del tamaño proporcionado
+    pub(super) fn calc_required_space(&self, size: usize) -> usize {
+        util::ceil_div(size, self.frame_size) * self.frame_size
+    }
+
+    /// Forma al proceso en la cola de procesos bloqueados, que esperan a que se libere memoria
+    pub(super) fn block(&mut self, pid: PID, size: usize, priority: u16) {
+        self.blocked.push_back((pid, size, priority));
+        self.emit(SimulationEvent::ProcessBlocked { pid, size });
+    }
+
+    /// Crea en orden de llegada los procesos bloqueados que ya caben en las memorias. Se detiene
+    /// en el primero que no cabe, para que los procesos más chicos no lo rebasen
+    /// Recibe el tiempo que ya tomó la instrucción y regresa el tiempo total. Si no se pudo crear
+    /// un proceso desbloqueado, éste se queda al frente de la cola y se emite el error, porque la
+    /// instrucción L ya liberó la memoria y su tiempo debe contar
+    pub(super) fn start_blocked_processes(&mut self, mut time_offset: Time) -> Time {
+        while let Some(&(pid, size, priority)) = self.blocked.front() {
+            let new_process = Process::new(pid, size, priority, self.frame_size);
+            if !self.can_load(&new_process) {
+                break;
+            }
+            self.emit(SimulationEvent::ProcessUnblocked { pid });
+            match self.load_process(new_process, time_offset) {
+                Ok(time) => {
+                    self.blocked.pop_front();
+                    time_offset = time;
+                }
+                Err(error) => {
+                    self.emit(SimulationEvent::UnblockFailed { pid, error });
+                    break;
+                }
+            }
+        }
+        time_offset
+    }
+
+    /// Regresa el error de una instrucción que no se pudo ejecutar aunque el OOM killer ya había
+    /// terminado procesos. Esos procesos ya se liberaron, por lo que el tiempo que tomó la
+    /// instrucción hasta ahora avanza el tiempo del sistema aunque la instrucción falle, y
+    /// `time_offset` regresa a cero para que no se cuente dos veces
+    pub(super) fn fail_after_kills(
+        &mut self,
+        error: SimulationError,
+        time_offset: &mut Time,
+    ) -> SimulationError {
+        if *time_offset != Time::new() {
+            self.advance_time(*time_offset);
+            *time_offset = Time::new();
+        }
+        error
+    }
+
+    /// Busca el marco de la memoria real en el que se cargará una página del proceso `pid`,
+    /// sacando otra página si es necesario (con System::get_frame_index_to_swap_into). Si no hay
+    /// espacio en el espacio swap para la página que sale y la respuesta es Kill, se terminan
+    /// otros procesos hasta que lo haya. Si aun así no hay espacio, el tiempo de liberar a los
+    /// procesos terminados avanza el tiempo del sistema (con System::fail_after_kills)
+    pub(super) fn get_frame_index_for_page(
+        &mut self,
+        pid: PID,
+        time_offset: &mut Time,
+    ) -> Result<usize, SimulationError> {
+        loop {
+            match self.get_frame_index_to_swap_into(pid, time_offset) {
+                Err(SimulationError::SwapExhausted)
+                    if self.swap_exhaustion == SwapExhaustionResponse::Kill =>
+                {
+                    let victim = match self.choose_victim(Some(pid)) {
+                        Some(victim) => victim,
+                        None => {
+                            return Err(
+                                self.fail_after_kills(SimulationError::SwapExhausted, time_offset)
+                            )
+                        }
+                    };
+                    *time_offset += self.kill(victim, *time_offset)?;
+                }
+                result => return result,
+            }
+        }
+    }
+}
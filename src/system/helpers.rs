@@ -1,162 +1,253 @@
 use std::collections::BTreeSet;
-use std::collections::HashMap;
-use std::iter::FromIterator;
-use std::mem::swap;
-use std::ops::Range;
 
 use super::{Frame, Memory, System, SWAP_PAGE_TIME};
-use crate::process::{ProcessPage, PID};
+use crate::error::SimulationError;
+use crate::event::SimulationEvent;
+use crate::process::{Process, ProcessPage, PID};
 use crate::time::Time;
-use crate::util;
 
 /// En éste archivo implementamos funciones auxiliares
 /// con el fin de aligerar el archivo principal (mod.rs)
 impl System {
     /// Encuentra una página especificando el pid y la índice de ella
-    /// Devuelve un Frame (como las coordenadas de un marco de página, declarado al final de mod.rs)
-    pub(super) fn find_page(&self, pid: PID, page_index: usize) -> Frame {
-        // Usamos una función que, pasándole una referencia a un marco de página, devuelve true si el
-        // marco contiene una página, y la página tiene el mismo pid e índice que los proporcionados
-        // a la función que la engloba
-        let frame_meets_conditions = |frame: &Option<ProcessPage>| -> bool {
-            frame.is_some() && frame.as_ref().unwrap().get_page_info() == (pid, page_index)
-        };
-
-        // Iteramos por la memoria real buscando si algún marco cumple con las condiciones
-        if let Some(m_index) = self.real_memory.iter().position(frame_meets_conditions) {
-            // En caso de encontrar uno regresamos un Frame con sus datos (una línea sin ; es un
-            // return implícito en Rust)
-            Frame(Memory::Real, m_index)
-        } else if let Some(s_index) = self.swap_space.iter().position(frame_meets_conditions) {
-            Frame(Memory::Swap, s_index)
-        } else {
-            // Si no encontramos la página significa que la memoria se corrompió por lo que provocamos un crash
-            panic!(
-                "No se encontró la página {} del proceso {}",
-                page_index, pid,
-            );
-        }
+    /// Devuelve un Frame (como las coordenadas de un marco de página, declarado al final de mod.rs),
+    /// o None si la página no está en ninguna memoria (con paginación por demanda, porque no se
+    /// ha accedido a ella)
+    pub(super) fn find_page(
+        &self,
+        pid: PID,
+        page_index: usize,
+    ) -> Result<Option<Frame>, SimulationError> {
+        // Consultamos directamente la tabla de páginas del proceso
+        self.alive_processes
+            .get(&pid)
+            .map(|process| process.get_page_frame(page_index))
+            .ok_or(SimulationError::UnknownPid(pid))
     }
 
     /// Si la página encontrada en una instrucción A está en espacio swap es necesario
     /// moverla a memoria real. Esta función busca qué espacio de la memoria real "le toca"
     /// ser swappeado.
-    /// Recibe una referencia (&) mutable (mut) a una instancia de tiempo para que en caso
-    /// de ser necesario añada el tiempo por swappear una página
-    pub(super) fn get_frame_index_to_swap_into(&mut self, time_offset: &mut Time) -> usize {
-        // Iteramos por la memoria real buscando un marco de página vacío (que no tenga página)
-        match self
-            .real_memory
-            .iter()
-            .enumerate()
-            .find(|(_, frame)| frame.is_none())
-        {
+    /// Recibe el pid del proceso al que le pertenece la página que entra (con el reemplazo local
+    /// sólo se puede reemplazar una página de ese proceso) y una referencia (&) mutable (mut) a
+    /// una instancia de tiempo para que en caso de ser necesario añada el tiempo por escribir en
+    /// el espacio swap la página reemplazada
+    pub(super) fn get_frame_index_to_swap_into(
+        &mut self,
+        pid: PID,
+        time_offset: &mut Time,
+    ) -> Result<usize, SimulationError> {
+        // Con cuotas (declarado en system/quotas.rs) el proceso puede tener que reemplazar una
+        // página aunque haya marcos vacíos
+        if let Some(frame_index_to_be_replaced) = self.find_page_to_replace_by_quota(pid) {
+            *time_offset += self.swap_out(frame_index_to_be_replaced)?;
+            return Ok(frame_index_to_be_replaced);
+        }
+        // Tomamos el primer marco de página vacío (que no tenga página) de la memoria real
+        match self.free_real_frames.iter().next() {
             // Si encontramos un espacio vacío, regresamos el índice
-            Some((index, _)) => return index,
+            Some(&index) => Ok(index),
             None => {
-                // En otro caso añadimos a la referencia de tiempo, el tiempo de swappear una página
-                *time_offset += SWAP_PAGE_TIME;
                 // Obtenemos el índice de marco en la memoria real al que "le toca ser swappeado"
-                let frame_index_to_be_replaced = self.find_page_to_replace();
-                // Obtenemos información sobre la página actualmente en el marco
-                let (pid, page_index) = self.real_memory[frame_index_to_be_replaced]
-                    .as_ref()
-                    .unwrap()
-                    .get_page_info();
-                // Añadimos un swap-out al proceso al que le pertenece esa página
-                self.alive_processes.get_mut(&pid).unwrap().add_swap_out();
-                println!("Swap out de la página {} del proceso {}", page_index, pid);
+                let frame_index_to_be_replaced = self.find_page_to_replace(pid);
+                // Sacamos la página de la memoria real
+                *time_offset += self.swap_out(frame_index_to_be_replaced)?;
                 // Regresamos el índice del marco
-                frame_index_to_be_replaced
+                Ok(frame_index_to_be_replaced)
             }
         }
     }
 
     /// Usamos esta función para obtener una lista de índices de marcos en memoria real en los que
     /// asignaremos nuestro proceso (por una instrucción P)
-    /// Recibe un número n (el número de marcos necesarios) y una referencia a una instancia de tiempo
-    pub(super) fn allocate_n_frames(&mut self, n: usize, time_offset: &mut Time) -> Vec<usize> {
-        // Generamos un set de índices iterando por la memoria real, filtrando los marcos que si
-        // tienen página, y mapeando los marcos vacíos a sus índices
-        let mut set_of_indexes = BTreeSet::from_iter(
-            self.real_memory
-                .iter()
-                .enumerate()
-                .filter_map(|(index, frame)| match frame {
-                    Some(_) => None,
-                    None => Some(index),
-                })
-                .into_iter(),
-        );
+    /// Recibe el proceso que se está creando, un número n (el número de marcos necesarios) y una
+    /// referencia a una instancia de tiempo
+    pub(super) fn allocate_n_frames(
+        &mut self,
+        new_process: &Process,
+        n: usize,
+        time_offset: &mut Time,
+    ) -> Result<Vec<usize>, SimulationError> {
+        // Generamos un set con los primeros n índices de los marcos vacíos de la memoria real
+        let mut set_of_indexes: BTreeSet<usize> =
+            self.free_real_frames.iter().take(n).copied().collect();
         // Si tuvimos espacio suficiente en memoria real (el número de marcos vacíos es mayor a los
-        // necesarios para el nuevo proceso) convertimos el set en lista, cortamos la lista al
-        // tamaño requerido y regresamos la lista
-        if set_of_indexes.len() >= n {
-            let mut result = Vec::from_iter(set_of_indexes.into_iter());
-            result.truncate(n);
-            return result;
+        // necesarios para el nuevo proceso) convertimos el set en lista y la regresamos
+        if set_of_indexes.len() == n {
+            return Ok(set_of_indexes.into_iter().collect());
+        }
+        // Con cuotas (declarado en system/quotas.rs) primero se reemplazan, una por una, páginas
+        // de los procesos que se pasan de la cuota que tendrán con el proceso nuevo, como en un
+        // fallo de página
+        while set_of_indexes.len() < n {
+            match self.find_page_to_replace_for_new_process(new_process) {
+                Some(frame_index_to_be_replaced) => {
+                    *time_offset += self.swap_out(frame_index_to_be_replaced)?;
+                    set_of_indexes.insert(frame_index_to_be_replaced);
+                }
+                None => break,
+            }
         }
-        // En otro caso pedimos el número de índices restantes a la función find_n_pages_to_replace,
+        // Y pedimos el número de índices restantes a la función find_n_pages_to_replace,
         // declarada en system/algorithms.rs que devuelve un set de índices
         let frame_indexes = self.find_n_pages_to_replace(n - set_of_indexes.len());
-        // Usamos una tabla hash mapeando el pid del proceso con un vector de rangos de índices
-        // para facilitar imprimir a qué páginas fue necesario hacerle swap-out
-        let mut swapped_out_ranges = HashMap::<PID, Vec<Range<usize>>>::new();
         // Por cada índice de marco
         for frame_index_to_be_replaced in frame_indexes {
-            // Añadimos el tiempo para hacerle swap-out
-            *time_offset += SWAP_PAGE_TIME;
-            // Obtenemos la información de la página que se encuentra en el marco
-            let (pid, page_index) = self.real_memory[frame_index_to_be_replaced]
-                .as_ref()
-                .unwrap()
-                .get_page_info();
-            // Añadimos la información de la página a nuestra tabla hash
-            match swapped_out_ranges.get_mut(&pid) {
-                Some(vec_of_ranges) => util::add_index_to_vec_of_ranges(page_index, vec_of_ranges),
-                None => {
-                    let mut new_vec_of_ranges = Vec::<Range<usize>>::new();
-                    util::add_index_to_vec_of_ranges(page_index, &mut new_vec_of_ranges);
-                    swapped_out_ranges.insert(pid, new_vec_of_ranges);
-                }
-            }
-            // Añadimos un swap out al proceso correspondiente
-            self.alive_processes.get_mut(&pid).unwrap().add_swap_out();
-            // Buscamos un marco en la memoria virtual vacío y obtenemos su índice
-            let (empty_frame_index_in_swap, _) = self
-                .swap_space
-                .iter()
-                .enumerate()
-                .find(|(_, maybe_frame)| maybe_frame.is_none())
-                .unwrap();
-            // Swapeamos la página de memoria real al marco vacío del espacio swap
-            swap(
-                &mut self.swap_space[empty_frame_index_in_swap],
-                &mut self.real_memory[frame_index_to_be_replaced],
-            );
+            // Sacamos la página de la memoria real y añadimos el tiempo que tomó
+            *time_offset += self.swap_out(frame_index_to_be_replaced)?;
             // Añadimos el índice al set de índices que declaramos al inicio de la función
             set_of_indexes.insert(frame_index_to_be_replaced);
         }
-        // Por cada pid en la tabla hash imprimimos un string de qué rangos de páginas se swapearon
-        swapped_out_ranges.iter().for_each(|(pid, ranges)| {
-            if let Some(ranges_str) = util::display_ranges_vec(&ranges) {
-                println!("Swap out de páginas del proceso {}: {}", pid, ranges_str);
-            }
-        });
         // Convertimos el set en un iterador y después en un vector (por la firma de la función)
-        set_of_indexes.into_iter().collect()
+        Ok(set_of_indexes.into_iter().collect())
+    }
+
+    /// Coloca la página en el marco de la memoria real proporcionado, que deja de estar vacío
+    pub(super) fn place_in_real_memory(&mut self, frame_index: usize, page: ProcessPage) {
+        self.free_real_frames.remove(&frame_index);
+        if page.get_swap_copy().is_some() {
+            self.copied_real_frames.insert(frame_index);
+        }
+        self.real_memory[frame_index] = Some(page);
+    }
+
+    /// Saca la página del marco de la memoria real proporcionado y regresa el marco a la lista de
+    /// marcos vacíos
+    pub(super) fn take_from_real_memory(&mut self, frame_index: usize) -> ProcessPage {
+        self.free_real_frames.insert(frame_index);
+        self.copied_real_frames.remove(&frame_index);
+        self.real_memory[frame_index].take().unwrap()
+    }
+
+    /// Coloca la página en el marco del espacio swap proporcionado, que deja de estar vacío
+    pub(super) fn place_in_swap_space(&mut self, swap_index: usize, page: ProcessPage) {
+        self.free_swap_frames.remove(&swap_index);
+        self.swap_space[swap_index] = Some(page);
+    }
+
+    /// Saca la página del marco del espacio swap proporcionado, si tiene, y regresa el marco a la
+    /// lista de marcos vacíos
+    pub(super) fn take_from_swap_space(&mut self, swap_index: usize) -> Option<ProcessPage> {
+        self.free_swap_frames.insert(swap_index);
+        self.swap_space[swap_index].take()
+    }
+
+    /// Saca la página del marco de memoria real proporcionado y deja el marco vacío
+    /// Si la página está limpia y ya tiene una copia válida en el espacio swap sólo se descarta,
+    /// en otro caso se escribe (write-back) en el espacio swap
+    /// Regresa el tiempo que tomó escribir la página, o un error si no hay espacio en el espacio
+    /// swap para escribirla (en ese caso la página se queda en la memoria real)
+    pub(super) fn swap_out(&mut self, frame_index: usize) -> Result<Time, SimulationError> {
+        let page = self.real_memory[frame_index].as_ref().unwrap();
+        let (pid, page_index) = page.get_page_info();
+        if !self.alive_processes.contains_key(&pid) {
+            return Err(SimulationError::CorruptState { pid, page_index });
+        }
+        // Si la página no se modificó desde que se leyó del espacio swap, la copia sigue siendo
+        // válida y no es necesario escribirla. Si se tiene que escribir y tenía una copia se
+        // sobreescribe, si no se busca un marco libre
+        let (swap_index, needs_write) = match page.get_swap_copy() {
+            Some(swap_index) => (swap_index, page.is_dirty()),
+            None => (
+                self.find_free_swap_frame()
+                    .ok_or(SimulationError::SwapExhausted)?,
+                true,
+            ),
+        };
+        // Avisamos a la política de reemplazo que la página sale de la memoria real
+        self.notify_evict(frame_index);
+        let mut page = self.take_from_real_memory(frame_index);
+        let process = self.alive_processes.get_mut(&pid).unwrap();
+        // Añadimos un swap-out al proceso al que le pertenece la página
+        process.add_swap_out();
+        // Y actualizamos su tabla de páginas
+        process.set_page_frame(page_index, Some(Frame(Memory::Swap, swap_index)));
+        if needs_write {
+            process.add_write_back();
+        }
+        self.emit(SimulationEvent::SwapOut {
+            pid,
+            page: page_index,
+            frame: frame_index,
+            swap_frame: swap_index,
+            write_back: needs_write,
+        });
+        if !needs_write {
+            return Ok(Time::new());
+        }
+        page.set_dirty(false);
+        page.set_swap_copy(None);
+        self.place_in_swap_space(swap_index, page);
+        Ok(SWAP_PAGE_TIME)
+    }
+
+    /// Busca un marco vacío en el espacio swap y regresa su índice
+    /// Si no hay marcos vacíos, descarta la copia en el espacio swap de alguna página que se
+    /// encuentre en memoria real (esa página tendrá que escribirse cuando salga de la memoria)
+    pub(super) fn find_free_swap_frame(&mut self) -> Option<usize> {
+        if let Some(&index) = self.free_swap_frames.iter().next() {
+            return Some(index);
+        }
+        let frame_index = *self.copied_real_frames.iter().next()?;
+        self.copied_real_frames.remove(&frame_index);
+        let page = self.real_memory[frame_index].as_mut().unwrap();
+        let swap_index = page.get_swap_copy().unwrap();
+        page.set_swap_copy(None);
+        self.take_from_swap_space(swap_index);
+        Some(swap_index)
+    }
+
+    /// Calcula cuántas páginas del proceso nuevo se cargan a la memoria real con la instrucción
+    /// P: las primeras páginas que caben en la memoria real y en la cuota de marcos que tendría
+    /// el proceso (declarado en system/quotas.rs). El resto van al espacio swap
+    pub(super) fn calc_resident_pages_on_load(&self, new_process: &Process) -> usize {
+        let quota = self.calc_new_process_quota(new_process);
+        new_process
+            .num_pages(self.frame_size)
+            .min(self.real_memory.len())
+            .min(System::calc_frame_limit(quota).unwrap_or(usize::MAX))
+    }
+
+    /// Calcula el espacio en bytes que puede usar el proceso nuevo en las memorias
+    /// Las páginas que se cargan a la memoria real usan primero sus marcos vacíos, y cada página
+    /// que se saca de la memoria real para hacerles espacio ocupa un marco del espacio swap, igual
+    /// que las páginas que se colocan directamente en el espacio swap. Con cuotas puede que el
+    /// proceso no use todos los marcos vacíos de la memoria real, por lo que el espacio puede ser
+    /// menor al espacio libre total
+    /// Con paginación por demanda no se carga ninguna página, pero cada proceso vivo reserva un
+    /// marco para cada una de sus páginas aunque todavía no se accedan, para que al cargarlas
+    /// haya espacio en las memorias
+    pub(super) fn calc_available_space(&self, new_process: &Process) -> usize {
+        if self.demand_paging {
+            let reserved_frames: usize = self
+                .alive_processes
+                .values()
+                .map(|process| process.num_pages(self.frame_size))
+                .sum();
+            let total_frames = self.real_memory.len() + self.swap_space.len();
+            return total_frames.saturating_sub(reserved_frames) * self.frame_size;
+        }
+        let free_space = self.calc_free_space();
+        let empty_real_frames = self.free_real_frames.len();
+        // Los marcos del espacio swap disponibles (vacíos o con copias que se pueden descartar)
+        let available_swap_frames = free_space / self.frame_size - empty_real_frames;
+        let resident_pages = self.calc_resident_pages_on_load(new_process);
+        (resident_pages.min(empty_real_frames) + available_swap_frames) * self.frame_size
+    }
+
+    /// Revisa si el proceso nuevo cabe en las memorias, antes de cambiar el estado del sistema
+    pub(super) fn can_load(&self, new_process: &Process) -> bool {
+        self.calc_required_space(new_process.get_size()) <= self.calc_available_space(new_process)
     }
 
     /// Calcula el espacio libre en el sistema en bytes
     pub(super) fn calc_free_space(&self) -> usize {
-        // Declaramos una función que recibe un número y un marco, y si el marco está vacío
-        // regresa el número más uno, y si no el número
-        let free_frames_accumulator =
-            |acc: usize, frame: &Option<_>| if frame.is_none() { acc + 1 } else { acc };
-
-        // Calculamos el número de marcos vacíos en memoria real y en espacio swap
-        let free_frames = self.real_memory.iter().fold(0, free_frames_accumulator)
-            + self.swap_space.iter().fold(0, free_frames_accumulator);
+        // Calculamos el número de marcos vacíos en memoria real y en espacio swap, contando también
+        // los marcos del espacio swap que sólo guardan la copia de una página en memoria real,
+        // porque se pueden liberar cuando se necesiten
+        let free_frames = self.free_real_frames.len()
+            + self.free_swap_frames.len()
+            + self.copied_real_frames.len();
 
         // Regresamos el número de marcos vacíos por el tamaño de cada marco
         self.frame_size * free_frames
@@ -1,15 +1,27 @@
-use std::collections::HashMap;
-use std::mem::swap;
-use std::ops::Range;
+use std::collections::{BTreeSet, HashMap, VecDeque};
 
-use crate::algorithm::PageReplacementAlgorithm;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::algorithm::ReplacementPolicy;
+use crate::error::SimulationError;
+use crate::event::{EventSink, SimulationEvent};
 use crate::process::{Process, ProcessPage, PID};
 use crate::time::Time;
 use crate::util;
 use crate::Instruction;
 
 mod algorithms;
+mod exhaustion;
 mod helpers;
+mod oom;
+mod quotas;
+mod report;
+
+pub use algorithms::ReplacementScope;
+pub use exhaustion::SwapExhaustionResponse;
+pub use oom::OomHeuristic;
+pub use quotas::FrameAllocation;
+pub use report::{ProcessReport, Report};
 
 const ACCESS_PAGE_TIME: Time = Time::from_miliseconds(100);
 const FREE_PAGE_TIME: Time = Time::from_miliseconds(100);
@@ -17,322 +29,690 @@ const LOAD_PAGE_TIME: Time = Time::from_miliseconds(1000);
 const MODIFY_PAGE_TIME: Time = Time::from_miliseconds(100);
 const SWAP_PAGE_TIME: Time = Time::from_miliseconds(1000);
 
+/// Opciones de configuración del sistema:
+/// - page_size: tamaño en bytes de una página
+/// - real_memory_size: tamaño en bytes de la memoria real
+/// - swap_space_size: tamaño en bytes del espacio swap
+/// - demand_paging: si es true, la instrucción P sólo crea la tabla de páginas del proceso y cada
+///   página se carga a la memoria real hasta que se accede por primera vez
+/// - swap_exhaustion: qué hace el sistema cuando no hay espacio en las memorias para un proceso
+///   o una página
+/// - oom_heuristic: cómo elige el OOM killer el proceso que se termina cuando la respuesta es
+///   terminar procesos
+/// - replacement_scope: si un proceso puede reemplazar páginas de otros procesos (global) o sólo
+///   las suyas (local)
+/// - frame_allocation: cómo se reparten los marcos de la memoria real en cuotas entre los procesos
+#[derive(Debug, Clone)]
+pub struct SystemOptions {
+    pub page_size: usize,
+    pub real_memory_size: usize,
+    pub swap_space_size: usize,
+    pub demand_paging: bool,
+    pub swap_exhaustion: SwapExhaustionResponse,
+    pub oom_heuristic: OomHeuristic,
+    pub replacement_scope: ReplacementScope,
+    pub frame_allocation: FrameAllocation,
+}
+
+impl Default for SystemOptions {
+    fn default() -> Self {
+        SystemOptions {
+            page_size: 16,
+            real_memory_size: 2048,
+            swap_space_size: 4096,
+            demand_paging: false,
+            swap_exhaustion: SwapExhaustionResponse::Reject,
+            oom_heuristic: OomHeuristic::Largest,
+            replacement_scope: ReplacementScope::Global,
+            frame_allocation: FrameAllocation::NoQuotas,
+        }
+    }
+}
+
 /// Encapsula el estado de un sistema, compuesto por:
-/// - algorithm: una variedad de PageReplacementAlgorithm usada para definir qué algoritmo se usa para reemplazar las páginas
-/// - time: el tiempo desde el inicio del sistema, medido en segundos
+/// - policy: la política de reemplazo (que implementa ReplacementPolicy) usada para definir qué página se reemplaza
+/// - time: el tiempo desde el inicio del escenario, medido en segundos
+/// - scenario: número del escenario actual, empezando en 1. Cada instrucción F termina un
+///   escenario y reinicia el sistema
+/// - finished: si el sistema ya terminó con una instrucción E
 /// - alive_processes: tabla de hash que mapea pid - instancias de Process
 /// - dead_processes: lista de instancias de Process ya liberados de la memoria
 /// - page_size: tamaño en bytes de una página
 /// - real_memory: lista de Option<ProcessPage> que corresponde a la memoria real
+/// - free_real_frames: set ordenado de los índices de los marcos vacíos de la memoria real, para
+///   no recorrerla buscándolos. Se actualiza cada vez que una página entra o sale de ella
 /// - swap_space: lista de Option<ProcessPage> que corresponde al espacio de paginación
+/// - free_swap_frames: set ordenado de los índices de los marcos vacíos del espacio swap
+/// - copied_real_frames: set ordenado de los índices de los marcos de la memoria real cuya página
+///   tiene una copia en el espacio swap, que se puede descartar si se necesita el marco
+/// - demand_paging: si las páginas se cargan hasta que se acceden (paginación por demanda)
+/// - swap_exhaustion: qué hace el sistema cuando no hay espacio en las memorias
+/// - oom_heuristic: heurística con la que se elige el proceso que se termina para liberar memoria
+/// - replacement_scope: de qué marcos se puede tomar la página que se reemplaza
+/// - frame_allocation: cómo se reparten los marcos de la memoria real en cuotas
+/// - blocked: cola de procesos (pid, tamaño, prioridad) bloqueados esperando a que se libere
+///   memoria
+/// - sinks: lista de receptores de los eventos que emite el sistema
 #[derive(Debug)]
 pub struct System {
-    algorithm: PageReplacementAlgorithm,
+    policy: Box<dyn ReplacementPolicy>,
     time: Time,
+    scenario: usize,
+    finished: bool,
     alive_processes: HashMap<PID, Process>,
     dead_processes: Vec<Process>,
     frame_size: usize,
     real_memory: Vec<Option<ProcessPage>>,
+    free_real_frames: BTreeSet<usize>,
     swap_space: Vec<Option<ProcessPage>>,
+    free_swap_frames: BTreeSet<usize>,
+    copied_real_frames: BTreeSet<usize>,
+    demand_paging: bool,
+    swap_exhaustion: SwapExhaustionResponse,
+    oom_heuristic: OomHeuristic,
+    replacement_scope: ReplacementScope,
+    frame_allocation: FrameAllocation,
+    blocked: VecDeque<(PID, usize, u16)>,
+    sinks: Vec<Box<dyn EventSink>>,
 }
 
 impl System {
     /// Crea una instancia del sistema tomando como argumentos:
-    /// - el algoritmo a usar
-    /// - el tamaño de página en bytes
-    /// - el tamaño de la memoria real en bytes
-    /// - el tamaño del espacio swap en bytes
-    pub fn new(
-        algorithm: PageReplacementAlgorithm,
-        page_size: usize,
-        real_memory_size: usize,
-        swap_space_size: usize,
-    ) -> Self {
-        let num_real_frames = util::ceil_div(real_memory_size, page_size);
-        let num_swap_frames = util::ceil_div(swap_space_size, page_size);
+    /// - la política de reemplazo a usar
+    /// - las opciones de configuración (tamaños de página, de memoria real y de espacio swap)
+    pub fn new(policy: Box<dyn ReplacementPolicy>, options: &SystemOptions) -> Self {
+        let num_real_frames = util::ceil_div(options.real_memory_size, options.page_size);
+        let num_swap_frames = util::ceil_div(options.swap_space_size, options.page_size);
         System {
-            algorithm,
+            policy,
             time: Time::new(),
+            scenario: 1,
+            finished: false,
             alive_processes: HashMap::new(),
             dead_processes: Vec::new(),
-            frame_size: page_size,
+            frame_size: options.page_size,
             real_memory: (0..num_real_frames).map(|_| None).collect(),
+            free_real_frames: (0..num_real_frames).collect(),
             swap_space: (0..num_swap_frames).map(|_| None).collect(),
+            free_swap_frames: (0..num_swap_frames).collect(),
+            copied_real_frames: BTreeSet::new(),
+            demand_paging: options.demand_paging,
+            swap_exhaustion: options.swap_exhaustion,
+            oom_heuristic: options.oom_heuristic,
+            replacement_scope: options.replacement_scope,
+            frame_allocation: options.frame_allocation,
+            blocked: VecDeque::new(),
+            sinks: Vec::new(),
         }
     }
 
+    /// Registra un receptor de los eventos del sistema. Sin sinks el sistema no imprime nada
+    pub fn add_sink(&mut self, sink: Box<dyn EventSink>) {
+        self.sinks.push(sink);
+    }
+
+    /// Le pasa el evento a cada uno de los sinks registrados
+    fn emit(&mut self, event: SimulationEvent) {
+        self.sinks.iter_mut().for_each(|sink| sink.handle(&event));
+    }
+
+    /// Get para el tiempo actual del sistema
+    pub fn get_time(&self) -> Time {
+        self.time
+    }
+
+    /// Get para el número del escenario actual
+    pub fn get_scenario(&self) -> usize {
+        self.scenario
+    }
+
+    /// Regresa si el sistema ya terminó con una instrucción E
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Get para el tamaño en bytes de una página
+    pub fn get_page_size(&self) -> usize {
+        self.frame_size
+    }
+
+    /// Regresa una referencia al proceso vivo con el pid proporcionado, si existe
+    pub fn get_process(&self, pid: PID) -> Option<&Process> {
+        self.alive_processes.get(&pid)
+    }
+
+    /// Regresa un iterador sobre los procesos que siguen vivos (en ningún orden en particular)
+    pub fn get_alive_processes(&self) -> impl Iterator<Item = &Process> {
+        self.alive_processes.values()
+    }
+
+    /// Get para una referencia a la lista de procesos ya liberados, en el orden en el que se
+    /// liberaron
+    pub fn get_dead_processes(&self) -> &[Process] {
+        &self.dead_processes
+    }
+
+    /// Get para una referencia a los marcos de la memoria real
+    pub fn get_real_memory(&self) -> &[Option<ProcessPage>] {
+        &self.real_memory
+    }
+
+    /// Get para una referencia a los marcos del espacio swap
+    pub fn get_swap_space(&self) -> &[Option<ProcessPage>] {
+        &self.swap_space
+    }
+
+    /// Le pasa a la política de reemplazo la lista completa de instrucciones que se van a
+    /// ejecutar, para las políticas que necesitan conocer los accesos futuros (como la óptima)
+    pub fn prepare(&mut self, trace: &[Instruction]) {
+        self.policy.prepare(trace, self.frame_size);
+    }
+
+    /// Le pasa la lista de instrucciones a la política de reemplazo y ejecuta cada una de ellas
+    /// en orden hasta la primera instrucción E. Los errores de una instrucción no detienen la
+    /// simulación, igual que en la consola. Regresa el resultado de cada instrucción ejecutada
+    pub fn run(&mut self, trace: &[Instruction]) -> Vec<Result<Time, SimulationError>> {
+        self.prepare(trace);
+        let mut results = Vec::new();
+        for instruction in trace {
+            results.push(self.process_instruction(instruction));
+            if self.finished {
+                break;
+            }
+        }
+        results
+    }
+
     /// Punto de entrada de las instrucciones
     /// Procesa una variante de Instruction pasada como referencia
     /// Dependiendo de si la instrucción es válida imprime un error o llama a la función correspondiente en el sistema
-    pub fn process_instruction(&mut self, instruction: &Instruction) {
+    /// Regresa el tiempo que tomó ejecutar la instrucción, o el error por el que no se pudo ejecutar
+    pub fn process_instruction(
+        &mut self,
+        instruction: &Instruction,
+    ) -> Result<Time, SimulationError> {
+        // Después de una instrucción E ya no se ejecuta ninguna instrucción
+        if self.finished {
+            return Err(SimulationError::SimulationFinished);
+        }
+        // Avisamos a la política de reemplazo y a los sinks que empieza una nueva instrucción
+        self.policy.on_instruction(instruction);
+        self.emit(SimulationEvent::InstructionStarted {
+            instruction: instruction.clone(),
+        });
         // Cada brazo del comando match devuelve una variedad de Result:
         // - Ok(Time) con el tiempo que llevó ejecutar la instrucción
-        // - Err(String) con un mensaje de error si no se pudo ejecutar la función
+        // - Err(SimulationError) con el error por el que no se pudo ejecutar la función
         let maybe_time_offset = match instruction {
-            Instruction::Process { pid, size } => {
-                if self.alive_processes.get(pid).is_some() {
-                    Err(format!(
-                        "Ya existe un proceso ejecutándose con el pid {}",
-                        *pid,
-                    ))
-                } else if *size > self.calc_free_space() {
-                    Err(format!(
-                        "El tamaño del proceso ({} bytes) es mayor a la memoria disponible en el sistema ({} bytes)",
-                        *size, self.calc_free_space(),
-                    ))
-                } else if *size > self.real_memory.len() * self.frame_size {
-                    Err(format!(
-                        "El tamaño del proceso ({} bytes) es mayor al de la memoria real ({} bytes)",
-                        *size, self.real_memory.len() * self.frame_size,
-                    ))
-                } else {
-                    Ok(self.process(*pid, *size))
-                }
-            }
+            Instruction::Process {
+                pid,
+                size,
+                priority,
+            } => self.process(*pid, *size, *priority),
             Instruction::Access {
                 pid,
                 address,
                 modifies,
-            } => {
-                if self.alive_processes.get(pid).is_none() {
-                    Err(format!(
-                        "No existe un proceso ejecutándose con el pid {}",
-                        *pid,
-                    ))
-                } else if !self
-                    .alive_processes
-                    .get(pid)
-                    .unwrap()
-                    .includes_address(*address)
-                {
-                    Err(format!(
-                        "El proceso {} no contiene la dirección virtual {}",
-                        *pid, *address,
-                    ))
-                } else {
-                    Ok(self.access(*pid, *address, *modifies))
-                }
-            }
-            Instruction::Free { pid } => {
-                if self.alive_processes.get(pid).is_none() {
-                    Err(format!(
-                        "No existe un proceso ejecutándose con el pid {}",
-                        *pid,
-                    ))
-                } else {
-                    Ok(self.free(*pid))
-                }
-            }
+            } => self.access(*pid, *address, *modifies),
+            Instruction::Free { pid } => self.free_instruction(*pid),
             Instruction::End() => {
                 self.end();
                 Ok(Time::new())
             }
-            Instruction::Comment(_) | Instruction::Exit() => Ok(Time::new()),
+            Instruction::Exit() => {
+                self.exit();
+                Ok(Time::new())
+            }
+            Instruction::Comment(_) => Ok(Time::new()),
         };
         // Si el Result fue:
-        match maybe_time_offset {
+        match &maybe_time_offset {
             Ok(time_offset) => {
                 // Ok -> se le suma el tiempo al tiempo del sistema
-                println!("La instrucción tomó {}", time_offset);
-                self.time += time_offset;
+                self.emit(SimulationEvent::InstructionFinished { time: *time_offset });
+                self.advance_time(*time_offset);
             }
-            // Error -> se imprime el error en la consola
-            Err(error_message) => println!("Error: {}", error_message),
+            // Error -> se emite el error
+            Err(error) => self.emit(SimulationEvent::InstructionFailed {
+                error: error.clone(),
+            }),
         };
+        maybe_time_offset
+    }
+
+    /// Le suma el tiempo al tiempo del sistema y avisa a la política de reemplazo y a los sinks
+    /// que avanzó el tiempo
+    fn advance_time(&mut self, time_offset: Time) {
+        self.time += time_offset;
+        self.policy
+            .on_time_advanced(self.time, &mut self.real_memory);
+        self.emit(SimulationEvent::TimeAdvanced { now: self.time });
+    }
+
+    /// Responde a las instrucciones P
+    /// Recibe el pid nuevo, el tamaño en bytes y la prioridad del proceso
+    fn process(
+        &mut self,
+        pid: PID,
+        total_size: usize,
+        priority: u16,
+    ) -> Result<Time, SimulationError> {
+        if self.alive_processes.contains_key(&pid) || self.is_blocked(pid) {
+            return Err(SimulationError::DuplicatePid(pid));
+        }
+        // Se instancia el proceso, que se agrega a los procesos vivos hasta que se carga
+        let new_process = Process::new(pid, total_size, priority, self.frame_size);
+        // Las páginas ocupan marcos completos, por lo que el espacio se compara en páginas
+        let required_space = self.calc_required_space(total_size);
+        let out_of_memory = self.out_of_memory(&new_process);
+        // Un proceso más grande que ambas memorias juntas nunca va a caber
+        if required_space > (self.real_memory.len() + self.swap_space.len()) * self.frame_size {
+            return Err(out_of_memory);
+        }
+        let mut time_offset = Time::new();
+        // Si ya hay procesos bloqueados el proceso se forma detrás de ellos
+        if self.swap_exhaustion == SwapExhaustionResponse::Block && !self.blocked.is_empty() {
+            self.block(pid, total_size, priority);
+            return Ok(time_offset);
+        }
+        // System::can_load (declarado en system/helpers.rs) revisa si caben las páginas del
+        // proceso tomando en cuenta su cuota de marcos
+        while !self.can_load(&new_process) {
+            match self.swap_exhaustion {
+                SwapExhaustionResponse::Reject => return Err(out_of_memory),
+                // Terminamos procesos (declarado en system/oom.rs) hasta que quepa
+                SwapExhaustionResponse::Kill => {
+                    let victim = match self.choose_victim(None) {
+                        Some(victim) => victim,
+                        None => return Err(self.fail_after_kills(out_of_memory, &mut time_offset)),
+                    };
+                    time_offset += self.kill(victim, time_offset)?;
+                }
+                SwapExhaustionResponse::Block => {
+                    self.block(pid, total_size, priority);
+                    return Ok(time_offset);
+                }
+            }
+        }
+        self.load_process(new_process, time_offset)
+    }
+
+    /// Regresa el error de una instrucción P para la que no hay espacio en las memorias
+    fn out_of_memory(&self, new_process: &Process) -> SimulationError {
+        SimulationError::OutOfMemory {
+            requested: new_process.get_size(),
+            available: self.calc_available_space(new_process),
+        }
     }
 
-    /// Responde a las instrucciones P válidas
-    /// Recibe el pid nuevo y el tamaño en bytes del proceso
-    fn process(&mut self, pid: PID, total_size: usize) -> Time {
-        // Se instancia el proceso
-        let mut new_process = Process::new(pid, total_size);
+    /// Carga las páginas del proceso y lo agrega a los procesos vivos. Antes de cambiar el estado
+    /// del sistema revisa que el proceso quepa en las memorias, por lo que si regresa un error el
+    /// sistema se queda como estaba
+    /// Recibe el proceso y el tiempo que ya tomó la instrucción, y regresa el tiempo total
+    fn load_process(
+        &mut self,
+        mut new_process: Process,
+        mut time_offset: Time,
+    ) -> Result<Time, SimulationError> {
+        let pid = new_process.get_pid();
+        let total_size = new_process.get_size();
+        if !self.can_load(&new_process) {
+            return Err(self.out_of_memory(&new_process));
+        }
         // Se calcula en número de páginas necesarias
         let pages_needed = new_process.num_pages(self.frame_size);
-        println!(
-            "Se asignaron {} bytes ({} páginas) al proceso {}",
-            total_size, pages_needed, pid,
-        );
-        let mut time_offset = Time::new();
+        self.emit(SimulationEvent::ProcessCreated {
+            pid,
+            size: total_size,
+            pages: pages_needed,
+            demand_paging: self.demand_paging,
+        });
+        // Con paginación por demanda no se carga ninguna página hasta que se acceda a ella
+        if self.demand_paging {
+            new_process.set_birth(self.time + time_offset);
+            self.alive_processes.insert(pid, new_process);
+            // Se vuelven a repartir los marcos contando al proceso nuevo (declarado en
+            // system/quotas.rs)
+            self.recalc_quotas();
+            return Ok(time_offset);
+        }
+        // Si el proceso es más grande que la memoria real (o que su cuota de marcos) sólo se
+        // cargan a ella las primeras páginas, y el resto se colocan directamente en el espacio
+        // swap
+        let resident_pages = self.calc_resident_pages_on_load(&new_process);
         // Implementamos System::allocate_n_frames en system/helpers.rs, que devuelve un
         // HashSet de índices en los que podemos colocar las páginas.
         // La llamada a .enumerate() convierte el iterador de índices en la memoria real
         // en un iterador de (índice de página, índice en la memoria real)
         for (page_index, empty_frame_index) in self
-            .allocate_n_frames(pages_needed, &mut time_offset)
+            .allocate_n_frames(&new_process, resident_pages, &mut time_offset)?
             .into_iter()
             .enumerate()
         {
             // Instanciamos la página del proceso en el espacio de memoria que le corresponde
-            self.real_memory[empty_frame_index] =
-                Some(ProcessPage::new(pid, page_index, self.time + time_offset));
+            self.place_in_real_memory(
+                empty_frame_index,
+                ProcessPage::new(pid, page_index, self.time + time_offset),
+            );
+            // Y registramos el marco en la tabla de páginas del proceso
+            new_process.set_page_frame(page_index, Some(Frame(Memory::Real, empty_frame_index)));
+            // Avisamos a la política de reemplazo que se cargó la página
+            self.notify_load(empty_frame_index);
+            self.emit(SimulationEvent::PageLoaded {
+                pid,
+                page: page_index,
+                frame: empty_frame_index,
+            });
             // Añadimos al tiempo de la función el tiempo que toma cargar una página
             time_offset += LOAD_PAGE_TIME;
         }
+        // Colocamos las páginas restantes en marcos libres del espacio swap. El sistema ya revisó
+        // que haya espacio suficiente para todas las páginas del proceso
+        for page_index in resident_pages..pages_needed {
+            let swap_index = self
+                .find_free_swap_frame()
+                .ok_or(SimulationError::SwapExhausted)?;
+            self.place_in_swap_space(
+                swap_index,
+                ProcessPage::new(pid, page_index, self.time + time_offset),
+            );
+            new_process.set_page_frame(page_index, Some(Frame(Memory::Swap, swap_index)));
+            // Añadimos el tiempo que toma escribir una página en el espacio swap
+            time_offset += SWAP_PAGE_TIME;
+            self.emit(SimulationEvent::PagePlacedInSwap {
+                pid,
+                page: page_index,
+                swap_frame: swap_index,
+            });
+        }
         // Asignamos el tiempo de "nacimiento" de nuestro proceso
         new_process.set_birth(self.time + time_offset);
         // Lo agregamos a la tabla de procesos vivos del sistema
         self.alive_processes.insert(pid, new_process);
+        // Y se vuelven a repartir los marcos contando al proceso nuevo
+        self.recalc_quotas();
         // En Rust, si la última línea no tiene ; se trata de un return implícito
         // Estamos regresando cuánto tiempo tomó ejecutar la función
-        time_offset
+        Ok(time_offset)
     }
 
-    /// Responde a las instrucciones A válidas
+    /// Responde a las instrucciones A
     /// Recibe el pid del proceso, la dirección virtual, y si modifica la página
-    fn access(&mut self, pid: PID, process_address: usize, modifies: bool) -> Time {
+    fn access(
+        &mut self,
+        pid: PID,
+        process_address: usize,
+        modifies: bool,
+    ) -> Result<Time, SimulationError> {
+        if self.is_blocked(pid) {
+            return Err(SimulationError::ProcessBlocked(pid));
+        }
+        if !self
+            .alive_processes
+            .get(&pid)
+            .ok_or(SimulationError::UnknownPid(pid))?
+            .includes_address(process_address)
+        {
+            return Err(SimulationError::AddressOutOfRange {
+                pid,
+                address: process_address,
+            });
+        }
         let mut time_offset = Time::new();
         // Calculamos el índice de la página del proceso en la que se encuentra la dirección
         let process_page_index = process_address / self.frame_size;
         // Obtenemos el índice en memoria real de la página que buscamos
-        let frame_index = match self.find_page(pid, process_page_index) {
-            // Si la página ya estaba en memoria real, devolvemos el index
-            Frame(Memory::Real, index) => index,
+        let frame_index = match self.find_page(pid, process_page_index)? {
+            // Si la página ya estaba en memoria real, añadimos a la cuenta de hits del proceso y
+            // devolvemos el index
+            Some(Frame(Memory::Real, index)) => {
+                self.alive_processes.get_mut(&pid).unwrap().add_hit();
+                index
+            }
             // Pero si la página se encuentra en el espacio swap, es necesario moverla
-            Frame(Memory::Swap, index) => {
-                // Añadimos a la cuenta de swap-ins del proceso un swap
-                self.alive_processes.get_mut(&pid).unwrap().add_swap_in();
+            Some(Frame(Memory::Swap, index)) => {
+                // Sacamos la página del espacio swap, dejando su marco libre por si se necesita
+                // para la página que se va a reemplazar
+                let mut page =
+                    self.take_from_swap_space(index)
+                        .ok_or(SimulationError::CorruptState {
+                            pid,
+                            page_index: process_page_index,
+                        })?;
                 // Buscamos con qué marco de la memoria real deberíamos swapear la página del espacio swap
-                let frame_index_to_swap = self.get_frame_index_to_swap_into(&mut time_offset);
-                swap(
-                    &mut self.real_memory[frame_index_to_swap],
-                    &mut self.swap_space[index],
-                );
-                println!(
-                    "Swap in de la página {} del proceso {}",
-                    process_page_index, pid,
+                let frame_index_to_swap = match self.get_frame_index_for_page(pid, &mut time_offset)
+                {
+                    Ok(frame_index) => frame_index,
+                    // Si no se pudo liberar un marco regresamos la página a su lugar
+                    Err(error) => {
+                        self.place_in_swap_space(index, page);
+                        return Err(error);
+                    }
+                };
+                // Añadimos a la cuenta de swap-ins y de fallos de página del proceso
+                let process = self.alive_processes.get_mut(&pid).unwrap();
+                process.add_swap_in();
+                process.add_page_fault(process_page_index);
+                self.emit(SimulationEvent::PageFault {
+                    pid,
+                    page: process_page_index,
+                });
+                // Si el marco del espacio swap sigue libre guardamos ahí una copia de la página,
+                // para no tener que escribirla de nuevo si sale de la memoria real sin modificarse
+                if self.swap_space[index].is_none() {
+                    self.place_in_swap_space(index, page.clone());
+                    page.set_swap_copy(Some(index));
+                }
+                self.place_in_real_memory(frame_index_to_swap, page);
+                self.alive_processes.get_mut(&pid).unwrap().set_page_frame(
+                    process_page_index,
+                    Some(Frame(Memory::Real, frame_index_to_swap)),
                 );
+                self.notify_load(frame_index_to_swap);
+                self.emit(SimulationEvent::SwapIn {
+                    pid,
+                    page: process_page_index,
+                    swap_frame: index,
+                    frame: frame_index_to_swap,
+                });
                 // Regresamos el índice del marco en la memoria real
                 frame_index_to_swap
             }
+            // Con paginación por demanda, si la página no está en ningún lado es porque es el
+            // primer acceso a ella, y es necesario cargarla
+            None if self.demand_paging => {
+                // Buscamos en qué marco de la memoria real se cargará la página
+                let frame_index = self.get_frame_index_for_page(pid, &mut time_offset)?;
+                self.alive_processes
+                    .get_mut(&pid)
+                    .unwrap()
+                    .add_page_fault(process_page_index);
+                self.emit(SimulationEvent::PageFault {
+                    pid,
+                    page: process_page_index,
+                });
+                self.place_in_real_memory(
+                    frame_index,
+                    ProcessPage::new(pid, process_page_index, self.time + time_offset),
+                );
+                self.alive_processes
+                    .get_mut(&pid)
+                    .unwrap()
+                    .set_page_frame(process_page_index, Some(Frame(Memory::Real, frame_index)));
+                // Añadimos el tiempo que toma cargar una página
+                time_offset += LOAD_PAGE_TIME;
+                self.notify_load(frame_index);
+                self.emit(SimulationEvent::PageLoaded {
+                    pid,
+                    page: process_page_index,
+                    frame: frame_index,
+                });
+                frame_index
+            }
+            // Si no encontramos la página significa que la memoria se corrompió
+            None => {
+                return Err(SimulationError::CorruptState {
+                    pid,
+                    page_index: process_page_index,
+                })
+            }
         };
-        println!(
-            "Se {} la dirección {} del proceso {} (página {})",
-            if modifies { "modificó" } else { "accedió a" },
-            process_address,
+        self.emit(SimulationEvent::PageAccessed {
             pid,
-            process_page_index,
-        );
-        println!(
-            "Esta dirección corresponde a la dirección {} en la memoria real (marco de página {})",
-            frame_index * self.frame_size + (process_address % self.frame_size),
-            frame_index,
-        );
+            address: process_address,
+            page: process_page_index,
+            frame: frame_index,
+            real_address: frame_index * self.frame_size + (process_address % self.frame_size),
+            modifies,
+        });
         // Añadimos al tiempo de la función dependiendo si se modificó la página
         time_offset += if modifies {
             MODIFY_PAGE_TIME
         } else {
             ACCESS_PAGE_TIME
         };
-        // Actualizamos el tiempo del último acceso a la página
-        self.real_memory[frame_index]
-            .as_mut()
-            .unwrap()
-            .update_accessed_time(self.time + time_offset);
+        // Prendemos el bit de referencia de la página
+        let page = self.real_memory[frame_index].as_mut().unwrap();
+        page.set_referenced(true);
+        // Si se modificó la página prendemos su bit de modificación (dirty)
+        if modifies {
+            page.set_dirty(true);
+        }
+        // Avisamos a la política de reemplazo del acceso
+        self.notify_access(frame_index, modifies);
         // Regresamos el tiempo de la función
-        time_offset
-    }
-
-    /// Responde a las instrucciones L válidas
-    /// Recibe el pid del proceso
-    fn free(&mut self, pid: PID) -> Time {
-        // Declaramos una función que recibe un índice y una referencia (&) mutable (mut) a una posible
-        // página del proceso, que si cumple con la condición de que la posible página es página
-        // y tiene el mismo pid que el que pasamos a la función borra la página y regresa el índice
-        let frame_is_freed =
-            |index: usize, maybe_frame: &mut Option<ProcessPage>| -> Option<usize> {
-                if maybe_frame.is_some() && maybe_frame.as_ref().unwrap().get_pid() == pid {
-                    *maybe_frame = None;
-                    Some(index)
-                } else {
-                    None
-                }
-            };
+        Ok(time_offset)
+    }
 
+    /// Libera los marcos del proceso y lo agrega a la lista de procesos muertos, por una
+    /// instrucción L o porque se terminó el proceso para liberar memoria
+    /// Recibe el pid del proceso y el tiempo que ya tomó la instrucción antes de liberarlo (por
+    /// ejemplo, terminando otros procesos), para calcular el tiempo de muerte del proceso
+    /// Regresa sólo el tiempo que tomó liberar los marcos
+    fn free(&mut self, pid: PID, elapsed: Time) -> Result<Time, SimulationError> {
         let mut time_offset = Time::new();
         // Saca el proceso de la lista de procesos vivos
-        let mut now_dead_process = self.alive_processes.remove(&pid).unwrap();
-        // Para facilitar la presentación de los marcos de memoria que se limpiaron usamos una lista
-        // de rangos de índices en vez de imprimir cada vez que se liberaba un marco
-        let mut r_freed_ranges = Vec::<Range<usize>>::new();
-        // Iteramos por la memoria real, metemos cada opción de página en un tuple
-        // (índice, opción de página) y
-        self.real_memory
-            .iter_mut()
-            .enumerate()
-            .for_each(|(index, maybe_frame)| {
-                // Checamos si se borró la página
-                if let Some(index) = frame_is_freed(index, maybe_frame) {
-                    // En ese caso sumamos el tiempo de liberación de página
-                    time_offset += FREE_PAGE_TIME;
-                    // Usamos una función auxiliar (declarada en util.rs) para añadir al índice
-                    // al rango de índices
-                    util::add_index_to_vec_of_ranges(index, &mut r_freed_ranges);
-                }
+        let mut now_dead_process = self
+            .alive_processes
+            .remove(&pid)
+            .ok_or(SimulationError::UnknownPid(pid))?;
+        // Juntamos los índices de los marcos que ocupa el proceso en cada memoria usando su
+        // tabla de páginas, para no tener que recorrer las memorias completas
+        let mut real_indexes = Vec::<usize>::new();
+        let mut swap_indexes = Vec::<usize>::new();
+        now_dead_process
+            .get_page_table()
+            .iter()
+            .flatten()
+            .for_each(|frame| match frame {
+                Frame(Memory::Real, index) => real_indexes.push(*index),
+                Frame(Memory::Swap, index) => swap_indexes.push(*index),
+            });
+        // Ordenamos los índices para imprimirlos como rangos
+        real_indexes.sort_unstable();
+        swap_indexes.sort_unstable();
+        for index in real_indexes {
+            // Antes de borrar la página de la memoria real avisamos a la política de reemplazo
+            self.notify_free(index);
+            let page = self.take_from_real_memory(index);
+            // La copia en el espacio swap de la página se descarta sin contar como página liberada
+            if let Some(swap_index) = page.get_swap_copy() {
+                self.take_from_swap_space(swap_index);
+            }
+            // Sumamos el tiempo de liberación de página
+            time_offset += FREE_PAGE_TIME;
+            self.emit(SimulationEvent::FrameFreed {
+                pid,
+                page: page.get_page_info().1,
+                frame: Frame(Memory::Real, index),
             });
-        // Usamos otra función auxiliar para imprimir los rangos de memoria real que se limpiaron
-        if let Some(ranges_str) = util::display_ranges_vec(&r_freed_ranges) {
-            println!("Se liberan de la memoria real: {}", ranges_str);
         }
         // Hacemos lo mismo para el espacio swap
-        let mut v_freed_ranges = Vec::<Range<usize>>::new();
-        self.swap_space
-            .iter_mut()
-            .enumerate()
-            .for_each(|(index, maybe_frame)| {
-                if let Some(index) = frame_is_freed(index, maybe_frame) {
-                    time_offset += FREE_PAGE_TIME;
-                    util::add_index_to_vec_of_ranges(index, &mut v_freed_ranges);
-                }
+        for index in swap_indexes {
+            let page = self.take_from_swap_space(index).unwrap();
+            time_offset += FREE_PAGE_TIME;
+            self.emit(SimulationEvent::FrameFreed {
+                pid,
+                page: page.get_page_info().1,
+                frame: Frame(Memory::Swap, index),
             });
-        if let Some(ranges_str) = util::display_ranges_vec(&v_freed_ranges) {
-            println!("Se liberan del espacio swap: {}", ranges_str);
         }
+        now_dead_process.clear_page_table();
         // Asignamos el tiempo de "muerte" al proceso
-        now_dead_process.set_death(self.time + time_offset);
+        now_dead_process.set_death(self.time + elapsed + time_offset);
         // Añadimos el proceso a la lista de procesos muertos
         self.dead_processes.push(now_dead_process);
+        // Y repartimos sus marcos entre los procesos que siguen vivos
+        self.recalc_quotas();
         // Regresamos el tiempo de la función
-        time_offset
+        Ok(time_offset)
+    }
+
+    /// Responde a las instrucciones L
+    /// Libera el proceso y después crea los procesos bloqueados que ya caben en las memorias. Si
+    /// el proceso estaba bloqueado sólo se saca de la cola
+    fn free_instruction(&mut self, pid: PID) -> Result<Time, SimulationError> {
+        let time_offset = match self
+            .blocked
+            .iter()
+            .position(|&(blocked, _, _)| blocked == pid)
+        {
+            Some(position) => {
+                self.blocked.remove(position);
+                self.emit(SimulationEvent::BlockedProcessRemoved { pid });
+                Time::new()
+            }
+            None => self.free(pid, Time::new())?,
+        };
+        Ok(self.start_blocked_processes(time_offset))
+    }
+
+    /// Regresa si el proceso con el pid proporcionado está bloqueado esperando memoria
+    fn is_blocked(&self, pid: PID) -> bool {
+        self.blocked.iter().any(|&(blocked, _, _)| blocked == pid)
     }
 
     /// Responde a las instrucciones F
+    /// Emite el reporte del escenario generado por la función report (declarada en
+    /// system/report.rs) y reinicia el sistema para el siguiente escenario
     fn end(&mut self) {
-        println!("Turnaround de cada proceso:");
-        // Por cada proceso muerto imprimimos su vida y su turnaround
-        self.dead_processes.iter().for_each(|process| {
-            println!(
-                "\tProceso {}:\t{:16}\t{} de turnaround",
-                process.get_pid(),
-                process.display_life(),
-                process.calc_turnaround(),
-            );
-        });
-        // Calculmos el tiempo de turnaround en milisegundos
-        let average_turnaround_in_ms = self
-            .dead_processes
-            .iter() // Iteramos por los procesos muertos
-            .map(|process| process.calc_turnaround()) // Mapeamos cada proceso a su turnaround
-            .fold(0.0, |sum, turnaround| sum + f64::from(turnaround)) // Sumamos el turnaround de cada uno
-            / self.dead_processes.len() as f64; // Lo dividimos entre el número de procesos muertos
-        println!(
-            "Turnaround promedio: {} segundos",
-            average_turnaround_in_ms / 1000.0,
-        );
-        println!("Swaps por proceso:");
-        // Por cada proceso muerto imprimimos su número de swap-ins y swap-outs
-        self.dead_processes.iter().for_each(|process| {
-            let (swap_ins, swap_outs) = process.get_swaps();
-            println!(
-                "\tProceso {}:\t{} swap-ins,\t{} swap-outs",
-                process.get_pid(),
-                swap_ins,
-                swap_outs,
-            );
+        let report = self.report();
+        self.emit(SimulationEvent::Report(report));
+        self.reset();
+    }
+
+    /// Responde a las instrucciones E
+    /// Emite el reporte final, que incluye a los procesos que siguen vivos, y marca el sistema
+    /// como terminado para que no ejecute más instrucciones
+    fn exit(&mut self) {
+        let report = self.report();
+        self.emit(SimulationEvent::Report(report));
+        self.finished = true;
+    }
+
+    /// Vacía ambas memorias, olvida los procesos vivos y muertos, regresa el tiempo a cero y
+    /// reinicia la política de reemplazo, empezando un nuevo escenario
+    fn reset(&mut self) {
+        self.alive_processes.clear();
+        self.dead_processes.clear();
+        self.blocked.clear();
+        self.real_memory.iter_mut().for_each(|frame| *frame = None);
+        self.free_real_frames = (0..self.real_memory.len()).collect();
+        self.swap_space.iter_mut().for_each(|frame| *frame = None);
+        self.free_swap_frames = (0..self.swap_space.len()).collect();
+        self.copied_real_frames.clear();
+        self.time = Time::new();
+        self.policy.reset();
+        self.scenario += 1;
+        self.emit(SimulationEvent::Reset {
+            scenario: self.scenario,
         });
     }
 }
 
 /// Usamos este enum para marcar en qué espacio de la memoria se encontraba un marco
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Memory {
     Real,
     Swap,
@@ -340,4 +720,37 @@ pub enum Memory {
 
 /// Usamos este struct para referirnos a un punto específico de la memoria
 /// Compuesto por: en qué tipo de memoria se encuentra y su índice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Frame(Memory, usize);
+
+impl Frame {
+    /// Constructor de un marco de la memoria real con el índice proporcionado
+    pub fn real(index: usize) -> Self {
+        Frame(Memory::Real, index)
+    }
+
+    /// Constructor de un marco del espacio swap con el índice proporcionado
+    pub fn swap(index: usize) -> Self {
+        Frame(Memory::Swap, index)
+    }
+
+    /// Get para el tipo de memoria en la que se encuentra el marco
+    pub fn get_memory(&self) -> Memory {
+        self.0
+    }
+
+    /// Get para el índice del marco dentro de su memoria
+    pub fn get_index(&self) -> usize {
+        self.1
+    }
+}
+
+/// Un marco se serializa como un objeto con el tipo de memoria y el índice
+impl Serialize for Frame {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Frame", 2)?;
+        state.serialize_field("memory", &self.0)?;
+        state.serialize_field("index", &self.1)?;
+        state.end()
+    }
+}
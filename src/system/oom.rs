@@ -0,0 +1,109 @@
+use std::cmp::Reverse;
+
+use serde::Serialize;
+
+use super::System;
+use crate::error::SimulationError;
+use crate::event::SimulationEvent;
+use crate::process::{Process, PID};
+use crate::time::Time;
+
+/// Usamos este enum para elegir la heurística con la que el OOM killer escoge el proceso que se
+/// termina cuando no hay espacio en las memorias:
+/// - Largest: el proceso con más páginas en la memoria real (y en caso de empate el más grande)
+/// - Newest: el proceso que nació más recientemente, que es el que menos trabajo pierde
+/// - Priority: el proceso con la menor prioridad (y en caso de empate el que tiene más páginas
+///   en la memoria real)
+/// - Badness: el proceso con la mayor puntuación de "maldad", parecida a la de Linux: la
+///   proporción (en milésimas) de los marcos de ambas memorias que ocupa el proceso, dividida
+///   entre su prioridad
+///
+/// En todos los casos los empates restantes se rompen eligiendo el proceso de mayor pid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OomHeuristic {
+    #[default]
+    Largest,
+    Newest,
+    Priority,
+    Badness,
+}
+
+impl OomHeuristic {
+    /// Lista de todas las variantes del enum
+    pub const ALL: [OomHeuristic; 4] = [
+        OomHeuristic::Largest,
+        OomHeuristic::Newest,
+        OomHeuristic::Priority,
+        OomHeuristic::Badness,
+    ];
+
+    /// Esta función se aplica a una variante del enum y regresa un string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OomHeuristic::Largest => "largest",
+            OomHeuristic::Newest => "newest",
+            OomHeuristic::Priority => "priority",
+            OomHeuristic::Badness => "badness",
+        }
+    }
+}
+
+impl System {
+    /// Calcula la puntuación de "maldad" del proceso para la heurística Badness
+    fn calc_badness(&self, process: &Process) -> usize {
+        let total_frames = self.real_memory.len() + self.swap_space.len();
+        let occupied_frames = process.get_page_table().iter().flatten().count();
+        occupied_frames * 1000 / total_frames / usize::from(process.get_priority())
+    }
+
+    /// Elige con la heurística del sistema el proceso que se terminará para liberar memoria, sin
+    /// contar al proceso `excluded`. Sólo se consideran los procesos que ocupan algún marco,
+    /// porque terminar a los demás no libera memoria, salvo con paginación por demanda, en la que
+    /// todos los procesos reservan marcos para sus páginas
+    /// Regresa None si no hay ningún proceso que se pueda terminar
+    pub(super) fn choose_victim(&self, excluded: Option<PID>) -> Option<PID> {
+        let candidates = self.alive_processes.values().filter(|process| {
+            Some(process.get_pid()) != excluded
+                && (self.demand_paging || process.get_page_table().iter().any(Option::is_some))
+        });
+        let victim = match self.oom_heuristic {
+            OomHeuristic::Largest => candidates.max_by_key(|process| {
+                (
+                    process.count_resident_pages(),
+                    process.get_size(),
+                    process.get_pid(),
+                )
+            }),
+            OomHeuristic::Newest => {
+                candidates.max_by_key(|process| (process.get_life().start, process.get_pid()))
+            }
+            OomHeuristic::Priority => candidates.max_by_key(|process| {
+                (
+                    Reverse(process.get_priority()),
+                    process.count_resident_pages(),
+                    process.get_pid(),
+                )
+            }),
+            OomHeuristic::Badness => {
+                candidates.max_by_key(|process| (self.calc_badness(process), process.get_pid()))
+            }
+        };
+        victim.map(|process| process.get_pid())
+    }
+
+    /// Termina el proceso para liberar sus marcos, igual que una instrucción L, y lo marca como
+    /// terminado por el OOM killer en la lista de procesos muertos
+    /// Recibe el tiempo que ya tomó la instrucción y regresa el tiempo que tomó liberar los marcos
+    pub(super) fn kill(&mut self, pid: PID, elapsed: Time) -> Result<Time, SimulationError> {
+        self.emit(SimulationEvent::ProcessKilled {
+            pid,
+            heuristic: self.oom_heuristic,
+        });
+        self.alive_processes
+            .get_mut(&pid)
+            .ok_or(SimulationError::UnknownPid(pid))?
+            .set_killed();
+        self.free(pid, elapsed)
+    }
+}
@@ -0,0 +1,192 @@
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+
+use super::System;
+use crate::process::{Process, PID};
+
+/// Usamos este enum para elegir cómo se reparten los marcos de la memoria real entre los procesos
+/// vivos. Cada proceso recibe una cuota de marcos, que se vuelve a calcular cada vez que se crea
+/// o se libera un proceso:
+/// - NoQuotas: no hay cuotas, cualquier proceso puede ocupar cualquier número de marcos
+/// - Equal: todos los procesos reciben el mismo número de marcos
+/// - Proportional: cada proceso recibe marcos en proporción a su tamaño
+/// - Priority: cada proceso recibe marcos en proporción a su prioridad
+///
+/// Los marcos que sobran al redondear se dan a los procesos con la mayor parte fraccionaria (y en
+/// caso de empate a los de menor pid), por lo que las cuotas siempre suman el número de marcos
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrameAllocation {
+    #[default]
+    NoQuotas,
+    Equal,
+    Proportional,
+    Priority,
+}
+
+impl FrameAllocation {
+    /// Lista de todas las variantes del enum
+    pub const ALL: [FrameAllocation; 4] = [
+        FrameAllocation::NoQuotas,
+        FrameAllocation::Equal,
+        FrameAllocation::Proportional,
+        FrameAllocation::Priority,
+    ];
+
+    /// Esta función se aplica a una variante del enum y regresa un string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FrameAllocation::NoQuotas => "none",
+            FrameAllocation::Equal => "equal",
+            FrameAllocation::Proportional => "proportional",
+            FrameAllocation::Priority => "priority",
+        }
+    }
+
+    /// Regresa el peso con el que el proceso participa en el reparto de los marcos
+    fn weight_of(&self, process: &Process) -> usize {
+        match self {
+            FrameAllocation::NoQuotas | FrameAllocation::Equal => 1,
+            FrameAllocation::Proportional => process.get_size(),
+            FrameAllocation::Priority => usize::from(process.get_priority()),
+        }
+    }
+}
+
+impl System {
+    /// Reparte los marcos de la memoria real entre los procesos vivos y el proceso
+    /// `new_process`, que todavía no está en la tabla de procesos vivos porque se está creando
+    /// Regresa una lista de tuples (pid, cuota) ordenada por pid, que está vacía si no hay cuotas
+    fn calc_quotas(&self, new_process: Option<&Process>) -> Vec<(PID, usize)> {
+        let allocation = self.frame_allocation;
+        if allocation == FrameAllocation::NoQuotas {
+            return Vec::new();
+        }
+        let mut processes: Vec<&Process> =
+            self.alive_processes.values().chain(new_process).collect();
+        processes.sort_by_key(|process| process.get_pid());
+        let weights: Vec<usize> = processes
+            .iter()
+            .map(|process| allocation.weight_of(process))
+            .collect();
+        let total_weight: usize = weights.iter().sum();
+        if total_weight == 0 {
+            return Vec::new();
+        }
+        // Cada proceso recibe la parte entera de su proporción de los marcos
+        let num_frames = self.real_memory.len();
+        let mut quotas: Vec<usize> = weights
+            .iter()
+            .map(|weight| num_frames * weight / total_weight)
+            .collect();
+        // Y los marcos restantes se reparten por la parte fraccionaria, de mayor a menor
+        let mut by_remainder: Vec<usize> = (0..processes.len()).collect();
+        by_remainder.sort_by_key(|&index| Reverse(num_frames * weights[index] % total_weight));
+        let leftover = num_frames - quotas.iter().sum::<usize>();
+        by_remainder
+            .into_iter()
+            .take(leftover)
+            .for_each(|index| quotas[index] += 1);
+        processes
+            .into_iter()
+            .map(|process| process.get_pid())
+            .zip(quotas)
+            .collect()
+    }
+
+    /// Calcula la cuota que tendría el proceso `new_process` si se crea, sin cambiar las cuotas
+    /// de los procesos vivos. Regresa None si no hay cuotas
+    pub(super) fn calc_new_process_quota(&self, new_process: &Process) -> Option<usize> {
+        self.calc_quotas(Some(new_process))
+            .into_iter()
+            .find(|&(pid, _)| pid == new_process.get_pid())
+            .map(|(_, quota)| quota)
+    }
+
+    /// Vuelve a repartir los marcos de la memoria real entre los procesos vivos, después de que
+    /// se crea o se libera un proceso
+    pub(super) fn recalc_quotas(&mut self) {
+        for (pid, quota) in self.calc_quotas(None) {
+            if let Some(process) = self.alive_processes.get_mut(&pid) {
+                process.set_quota(Some(quota));
+            }
+        }
+    }
+
+    /// Regresa el número de marcos que el proceso puede ocupar. Todos los procesos pueden tener
+    /// por lo menos una página en la memoria real, aunque su cuota sea cero, para poder
+    /// accederla
+    pub(super) fn calc_frame_limit(quota: Option<usize>) -> Option<usize> {
+        quota.map(|quota| quota.max(1))
+    }
+
+    /// Con cuotas, elige el marco de la memoria real que se reemplaza para cargar una página del
+    /// proceso `pid`:
+    /// - si el proceso ya ocupa todos los marcos de su cuota, una de sus propias páginas aunque
+    ///   haya marcos vacíos
+    /// - si no, y la memoria real está llena, una página de los procesos que ocupan más marcos
+    ///   que su cuota
+    ///
+    /// Regresa None si no hay cuotas o si se puede usar un marco vacío o elegir la página como
+    /// sin cuotas (porque ningún proceso se pasa de su cuota)
+    pub(super) fn find_page_to_replace_by_quota(&mut self, pid: PID) -> Option<usize> {
+        let process = self.alive_processes.get(&pid)?;
+        let limit = Self::calc_frame_limit(process.get_quota())?;
+        let resident_pages = process.count_resident_pages();
+        if resident_pages > 0 && resident_pages >= limit {
+            let own_frames = self.get_resident_frames(pid);
+            return Some(
+                self.policy
+                    .find_page_to_replace_among(&mut self.real_memory, &own_frames),
+            );
+        }
+        if !self.free_real_frames.is_empty() {
+            return None;
+        }
+        self.find_over_quota_page_to_replace(None)
+    }
+
+    /// Con cuotas, elige el marco de la memoria real que se reemplaza para cargar una página del
+    /// proceso `new_process`, que se está creando con la instrucción P y la memoria real está
+    /// llena: una página de los procesos que ocupan más marcos que la cuota que tendrán después
+    /// de crearlo
+    /// Regresa None si no hay cuotas o si ningún proceso se pasa de su cuota
+    pub(super) fn find_page_to_replace_for_new_process(
+        &mut self,
+        new_process: &Process,
+    ) -> Option<usize> {
+        self.find_over_quota_page_to_replace(Some(new_process))
+    }
+
+    /// Elige con la política de reemplazo una página de los procesos que ocupan más marcos que su
+    /// cuota. Si se está creando el proceso `new_process`, se usan las cuotas que tendrán los
+    /// procesos vivos después de crearlo
+    /// Regresa None si no hay cuotas o si ningún proceso se pasa de su cuota
+    fn find_over_quota_page_to_replace(&mut self, new_process: Option<&Process>) -> Option<usize> {
+        let new_quotas = new_process.map(|new_process| self.calc_quotas(Some(new_process)));
+        // Cada proceso lleva la cuenta de sus páginas en la memoria real, por lo que sólo
+        // recorremos las tablas de páginas de los procesos que se pasan de su cuota
+        let over_quota: BTreeSet<usize> = self
+            .alive_processes
+            .values()
+            .filter(|process| {
+                let quota = match &new_quotas {
+                    Some(quotas) => quotas
+                        .iter()
+                        .find(|&&(pid, _)| pid == process.get_pid())
+                        .map(|&(_, quota)| quota),
+                    None => process.get_quota(),
+                };
+                Self::calc_frame_limit(quota)
+                    .is_some_and(|limit| process.count_resident_pages() > limit)
+            })
+            .flat_map(|process| self.get_resident_frames(process.get_pid()))
+            .collect();
+        if over_quota.is_empty() {
+            return None;
+        }
+        Some(
+            self.policy
+                .find_page_to_replace_among(&mut self.real_memory, &over_quota),
+        )
+    }
+}
@@ -0,0 +1,280 @@
+use std::io;
+
+use serde::Serialize;
+
+use super::System;
+use crate::instruction::Instruction;
+use crate::process::{Process, PID};
+use crate::time::Time;
+
+/// Estadísticas de un proceso:
+/// - pid: número que identifica el proceso
+/// - size: tamaño del proceso en bytes
+/// - pages: número de páginas que ocupó el proceso
+/// - quota: cuota de marcos de la memoria real del proceso en el momento del reporte (o cuando se
+///   liberó), o None si el sistema no reparte los marcos en cuotas
+/// - freed: si el proceso ya se liberó. Si sigue vivo su vida es parcial: la muerte es el tiempo
+///   del reporte
+/// - killed: si el OOM killer terminó el proceso para liberar memoria (también cuenta como
+///   liberado)
+/// - birth: tiempo del sistema en el que las páginas del proceso terminaron de cargarse
+/// - death: tiempo del sistema en el que las páginas del proceso terminaron de liberarse
+/// - turnaround: tiempo desde el nacimiento hasta la muerte del proceso
+/// - swap_ins, swap_outs, write_backs, hits y page_faults: contadores del proceso
+/// - compulsory_faults, capacity_faults: los fallos de página divididos en obligatorios (la página
+///   nunca había estado en la memoria real) y de capacidad. No hay fallos de conflicto porque la
+///   memoria real es totalmente asociativa
+/// - fault_rate: proporción de los accesos del proceso que fueron fallos de página (None si el
+///   proceso no tuvo accesos)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProcessReport {
+    pub pid: PID,
+    pub size: usize,
+    pub pages: usize,
+    pub quota: Option<usize>,
+    pub freed: bool,
+    pub killed: bool,
+    pub birth: Time,
+    pub death: Time,
+    pub turnaround: Time,
+    pub swap_ins: usize,
+    pub swap_outs: usize,
+    pub write_backs: usize,
+    pub hits: usize,
+    pub page_faults: usize,
+    pub compulsory_faults: usize,
+    pub capacity_faults: usize,
+    pub fault_rate: Option<f64>,
+}
+
+/// Reporte que genera el sistema con la instrucción F al terminar un escenario:
+/// - scenario: número del escenario, empezando en 1
+/// - processes: estadísticas de cada proceso liberado, en el orden en el que se liberaron, y
+///   después las de los procesos que siguen vivos, en el orden en el que nacieron
+/// - average_turnaround: turnaround promedio de todos los procesos en segundos, contando la
+///   vida parcial de los que siguen vivos (None si no hay procesos)
+/// - hits, page_faults, compulsory_faults, capacity_faults: contadores de todo el sistema en el
+///   escenario, que incluyen a los procesos que siguen vivos
+/// - fault_rate: proporción de todos los accesos que fueron fallos de página (None si no ha
+///   habido accesos)
+/// - blocked: pids de los procesos que siguen bloqueados esperando memoria, en orden de llegada
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Report {
+    pub scenario: usize,
+    pub processes: Vec<ProcessReport>,
+    pub average_turnaround: Option<f64>,
+    pub hits: usize,
+    pub page_faults: usize,
+    pub compulsory_faults: usize,
+    pub capacity_faults: usize,
+    pub fault_rate: Option<f64>,
+    pub blocked: Vec<PID>,
+}
+
+/// Calcula la proporción de los accesos que fueron fallos de página (None si no hubo accesos)
+fn fault_rate(hits: usize, page_faults: usize) -> Option<f64> {
+    if hits + page_faults == 0 {
+        None
+    } else {
+        Some(page_faults as f64 / (hits + page_faults) as f64)
+    }
+}
+
+/// Calcula el promedio de los valores (None si no hay valores)
+fn average<I: Iterator<Item = f64>>(values: I) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+    if count == 0 {
+        None
+    } else {
+        Some(sum / count as f64)
+    }
+}
+
+/// Escribe un valor opcional en un campo del formato CSV, que queda vacío si no hay valor
+fn csv_field(value: Option<f64>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+impl System {
+    /// Genera el reporte del escenario actual con las estadísticas de los procesos que ya se
+    /// liberaron y de los que siguen vivos
+    pub fn report(&self) -> Report {
+        let mut alive_processes: Vec<&Process> = self.alive_processes.values().collect();
+        alive_processes.sort_by_key(|process| (process.get_life().start, process.get_pid()));
+        let processes: Vec<ProcessReport> = self
+            .dead_processes
+            .iter()
+            .map(|process| (process, true))
+            .chain(alive_processes.into_iter().map(|process| (process, false)))
+            .map(|(process, freed)| {
+                let (swap_ins, swap_outs) = process.get_swaps();
+                let life = process.get_life();
+                // Los procesos vivos mueren en el tiempo del reporte
+                let death = if freed { life.end } else { self.time };
+                let (compulsory_faults, capacity_faults) = process.get_fault_kinds();
+                ProcessReport {
+                    pid: process.get_pid(),
+                    size: process.get_size(),
+                    pages: process.num_pages(self.frame_size),
+                    quota: process.get_quota(),
+                    freed,
+                    killed: process.is_killed(),
+                    birth: life.start,
+                    death,
+                    turnaround: death - life.start,
+                    swap_ins,
+                    swap_outs,
+                    write_backs: process.get_write_backs(),
+                    hits: process.get_hits(),
+                    page_faults: process.get_page_faults(),
+                    compulsory_faults,
+                    capacity_faults,
+                    fault_rate: fault_rate(process.get_hits(), process.get_page_faults()),
+                }
+            })
+            .collect();
+        // Sumamos los contadores de los procesos vivos y muertos
+        let (mut hits, mut compulsory_faults, mut capacity_faults) = (0, 0, 0);
+        for process in self.alive_processes.values().chain(&self.dead_processes) {
+            let (compulsory, capacity) = process.get_fault_kinds();
+            hits += process.get_hits();
+            compulsory_faults += compulsory;
+            capacity_faults += capacity;
+        }
+        let page_faults = compulsory_faults + capacity_faults;
+        // Calculamos el tiempo de turnaround promedio en milisegundos y lo pasamos a segundos
+        let average_turnaround = average(
+            processes
+                .iter()
+                .map(|process| f64::from(process.turnaround)),
+        )
+        .map(|average_in_ms| average_in_ms / 1000.0);
+        Report {
+            scenario: self.scenario,
+            processes,
+            average_turnaround,
+            hits,
+            page_faults,
+            compulsory_faults,
+            capacity_faults,
+            fault_rate: fault_rate(hits, page_faults),
+            blocked: self.blocked.iter().map(|&(pid, _, _)| pid).collect(),
+        }
+    }
+
+    /// Le pasa la lista de instrucciones a la política de reemplazo, ejecuta cada una de ellas en
+    /// orden hasta la primera instrucción E, y regresa el reporte de cada escenario: los reportes
+    /// de las instrucciones F y E y, si después de la última F hubo instrucciones P, A o L sin
+    /// una E, el reporte del escenario sin terminar
+    pub fn run_scenarios(&mut self, trace: &[Instruction]) -> Vec<Report> {
+        self.prepare(trace);
+        let mut reports = Vec::new();
+        let mut pending = false;
+        for instruction in trace {
+            match instruction {
+                // El reporte se genera antes de que la instrucción F reinicie el sistema
+                Instruction::End() | Instruction::Exit() => {
+                    reports.push(self.report());
+                    pending = false;
+                }
+                Instruction::Comment(_) => {}
+                _ => pending = true,
+            }
+            let _ = self.process_instruction(instruction);
+            if self.finished {
+                break;
+            }
+        }
+        if pending {
+            reports.push(self.report());
+        }
+        reports
+    }
+}
+
+impl Report {
+    /// Encabezado de las columnas del formato CSV de los reportes
+    pub const CSV_HEADER: &'static str = "scenario,pid,size,pages,quota,freed,killed,birth,death,turnaround,swap_ins,swap_outs,write_backs,hits,page_faults,compulsory_faults,capacity_faults,fault_rate";
+
+    /// Escribe el reporte en formato CSV, con el encabezado y los renglones del reporte
+    pub fn write_csv<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "{}", Report::CSV_HEADER)?;
+        self.write_csv_rows(writer)
+    }
+
+    /// Escribe los renglones del reporte en formato CSV, sin el encabezado, para poder escribir
+    /// los reportes de varios escenarios en el mismo archivo. Cada renglón empieza con el número
+    /// del escenario. Hay un renglón por proceso con su pid, tamaño, páginas, cuota de marcos
+    /// (vacía si no hay cuotas), si ya se liberó, si
+    /// lo terminó el OOM killer, nacimiento, muerte, turnaround (los tiempos en segundos), swap-ins, swap-outs,
+    /// write-backs, hits, fallos de página (en total, obligatorios y de capacidad) y tasa de
+    /// fallos (vacía si el proceso no tuvo accesos), un renglón con el promedio de cada columna
+    /// numérica (vacíos si no hay procesos; el de la tasa de fallos sólo toma en cuenta a los
+    /// procesos con accesos) y un último renglón con los contadores y la tasa de fallos de todo
+    /// el sistema
+    pub fn write_csv_rows<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        for process in &self.processes {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                self.scenario,
+                process.pid,
+                process.size,
+                process.pages,
+                process
+                    .quota
+                    .map(|quota| quota.to_string())
+                    .unwrap_or_default(),
+                process.freed,
+                process.killed,
+                f64::from(process.birth) / 1000.0,
+                f64::from(process.death) / 1000.0,
+                f64::from(process.turnaround) / 1000.0,
+                process.swap_ins,
+                process.swap_outs,
+                process.write_backs,
+                process.hits,
+                process.page_faults,
+                process.compulsory_faults,
+                process.capacity_faults,
+                csv_field(process.fault_rate),
+            )?;
+        }
+        // Calcula el promedio de un valor de los procesos para el campo del formato CSV
+        let average_field = |value: &dyn Fn(&ProcessReport) -> f64| {
+            csv_field(average(self.processes.iter().map(value)))
+        };
+        // El nacimiento y la muerte no tienen un promedio útil, así que se dejan vacíos
+        writeln!(
+            writer,
+            "{},average,{},{},,,,,,{},{},{},{},{},{},{},{},{}",
+            self.scenario,
+            average_field(&|process| process.size as f64),
+            average_field(&|process| process.pages as f64),
+            csv_field(self.average_turnaround),
+            average_field(&|process| process.swap_ins as f64),
+            average_field(&|process| process.swap_outs as f64),
+            average_field(&|process| process.write_backs as f64),
+            average_field(&|process| process.hits as f64),
+            average_field(&|process| process.page_faults as f64),
+            average_field(&|process| process.compulsory_faults as f64),
+            average_field(&|process| process.capacity_faults as f64),
+            csv_field(average(
+                self.processes
+                    .iter()
+                    .filter_map(|process| process.fault_rate)
+            )),
+        )?;
+        // El sistema sólo tiene contadores de accesos
+        writeln!(
+            writer,
+            "{},system,,,,,,,,,,,,{},{},{},{},{}",
+            self.scenario,
+            self.hits,
+            self.page_faults,
+            self.compulsory_faults,
+            self.capacity_faults,
+            csv_field(self.fault_rate),
+        )
+    }
+}
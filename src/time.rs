@@ -2,10 +2,13 @@ use std::cmp;
 use std::fmt;
 use std::ops;
 
+use serde::Serialize;
+
 /// Para guardar el tiempo, en vez de guardarlo como un número con punto flotante implementamos
 /// una estructura que guarda el tiempo en milésimas de segundo como un número no negativo
 /// (u de unsigned) de 32 bits (32 de u32)
-#[derive(Debug, Clone, Copy)]
+/// Al serializarse se escribe como el número de milisegundos
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct Time(u32);
 
 impl Time {
@@ -19,12 +22,24 @@ impl Time {
         Time(ms)
     }
 
+    /// Regresa el tiempo en milisegundos
+    pub fn as_miliseconds(&self) -> u32 {
+        self.0
+    }
+
     /// Constructor de Time con un valor inicial máximo
     pub fn max() -> Self {
         Time(u32::MAX)
     }
 }
 
+/// El valor por defecto de Time es 0
+impl Default for Time {
+    fn default() -> Self {
+        Time::new()
+    }
+}
+
 /// Este trait (interfaz en idioma Rust) permite definir una forma de imprimir el valor personalizada
 impl fmt::Display for Time {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -72,7 +87,7 @@ impl cmp::Eq for Time {}
 /// Ordenamiento parcial
 impl cmp::PartialOrd for Time {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
-        Some(self.0.cmp(&other.0))
+        Some(self.cmp(other))
     }
 }
 
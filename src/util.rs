@@ -1,6 +1,6 @@
 use std::ops::Range;
 
-use crate::process::PID;
+use crate::process::{DEFAULT_PRIORITY, PID};
 
 /// Esta función recibe dos números enteros y los divide regresando el número entero
 /// igual o mayor a la división
@@ -56,6 +56,26 @@ pub fn string_to_usize(
     )
 }
 
+/// Esta función se encarga de parsear un string opcional y regresar la prioridad de un proceso,
+/// que se escribe como `prio=N` con N un entero positivo. Si el string no existe o no empieza con
+/// `prio=` (porque el resto de la línea es un comentario) se regresa la prioridad por defecto. Si
+/// empieza con `prio=` pero N no es un entero positivo que quepa en un u16, se regresa un error
+pub fn string_to_priority(maybe_string: Option<&str>) -> Result<u16, String> {
+    match maybe_string.and_then(|string| string.strip_prefix("prio=")) {
+        None => Ok(DEFAULT_PRIORITY),
+        Some(value) => match parse_string(
+            Some(value),
+            "P",
+            format!("un entero positivo no mayor a {}", u16::MAX),
+        )? {
+            0 => Err(String::from(
+                "La prioridad de un proceso debe ser mayor a 0",
+            )),
+            priority => Ok(priority),
+        },
+    }
+}
+
 /// Esta función se encarga de parsear un string y regresar un booleano. En el caso de que el
 /// parseo sea exitoso se retornará un outcome exitoso, en el caso contrario se retornará un
 /// outcome fallido y su correspondiente mensaje de error
@@ -74,7 +94,7 @@ pub fn string_to_bool(maybe_string: Option<&str>, instruction_name: &str) -> Res
 
 /// Esta función se encarga de retornar un string que represente un rango sólo si el vector
 /// tiene elementos
-pub fn display_ranges_vec(vector: &Vec<Range<usize>>) -> Option<String> {
+pub fn display_ranges_vec(vector: &[Range<usize>]) -> Option<String> {
     if vector.is_empty() {
         None
     } else {
@@ -108,3 +128,30 @@ pub fn add_index_to_vec_of_ranges(index: usize, vec_of_ranges: &mut Vec<Range<us
         }),
     }
 }
+
+/// Esta función recibe una lista de renglones de una tabla y regresa un string con la tabla,
+/// alineando cada columna al ancho de su celda más larga
+pub fn display_table(rows: &[Vec<String>]) -> String {
+    // Calculamos el ancho de cada columna
+    let num_columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..num_columns)
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    rows.iter()
+        .map(|row| {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ");
+            format!("{}\n", line.trim_end())
+        })
+        .collect()
+}
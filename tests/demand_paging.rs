@@ -0,0 +1,49 @@
+use std::convert::TryFrom;
+
+use memory_admin_simulator::{
+    Instruction, PolicyOptions, PolicyRegistry, SimulationError, System, SystemOptions,
+};
+
+/// Crea un sistema con FIFO, paginación por demanda, 4 marcos de memoria real y 4 marcos de
+/// espacio swap
+fn system_with_demand_paging() -> System {
+    let policy = PolicyRegistry::default()
+        .create("fifo", &PolicyOptions::default())
+        .unwrap();
+    let options = SystemOptions {
+        page_size: 16,
+        real_memory_size: 64,
+        swap_space_size: 64,
+        demand_paging: true,
+        ..SystemOptions::default()
+    };
+    System::new(policy, &options)
+}
+
+/// Ejecuta una instrucción escrita como en el archivo de entrada
+fn run(system: &mut System, line: &str) -> Result<(), SimulationError> {
+    let instruction = Instruction::try_from(line).unwrap();
+    system.process_instruction(&instruction).map(|_| ())
+}
+
+#[test]
+fn processes_reserve_space_for_pages_not_yet_accessed() {
+    let mut system = system_with_demand_paging();
+    run(&mut system, "P 128 1").unwrap();
+    // El proceso 1 ya reservó los 8 marcos de las memorias aunque no haya accedido a ninguno
+    match run(&mut system, "P 128 2") {
+        Err(SimulationError::OutOfMemory {
+            requested,
+            available,
+        }) => {
+            assert_eq!((requested, available), (128, 0))
+        }
+        other => panic!("Se esperaba OutOfMemory, se obtuvo {:?}", other),
+    }
+    // Todas las páginas del proceso admitido se pueden cargar
+    for address in (0..128).step_by(16) {
+        run(&mut system, &format!("A {} 1 1", address)).unwrap();
+    }
+    assert_eq!(system.get_real_memory().iter().flatten().count(), 4);
+    assert_eq!(system.get_swap_space().iter().flatten().count(), 4);
+}
@@ -0,0 +1,163 @@
+use std::cell::RefCell;
+use std::convert::TryFrom;
+use std::rc::Rc;
+
+use memory_admin_simulator::{
+    Frame, Instruction, PolicyOptions, PolicyRegistry, SimulationError, SimulationEvent, System,
+    SystemOptions, Time,
+};
+
+/// Sistema con FIFO, 2 marcos de memoria real y 4 marcos de espacio swap, que guarda en una
+/// lista los eventos que emite
+struct RecordedSystem {
+    system: System,
+    events: Rc<RefCell<Vec<SimulationEvent>>>,
+}
+
+impl RecordedSystem {
+    fn new() -> Self {
+        let policy = PolicyRegistry::default()
+            .create("fifo", &PolicyOptions::default())
+            .unwrap();
+        let options = SystemOptions {
+            page_size: 16,
+            real_memory_size: 32,
+            swap_space_size: 64,
+            ..SystemOptions::default()
+        };
+        let mut system = System::new(policy, &options);
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let sink_events = Rc::clone(&events);
+        system.add_sink(Box::new(move |event: &SimulationEvent| {
+            sink_events.borrow_mut().push(event.clone())
+        }));
+        RecordedSystem { system, events }
+    }
+
+    /// Ejecuta una instrucción escrita como en el archivo de entrada y regresa los eventos que
+    /// emitió
+    fn run(&mut self, line: &str) -> Vec<SimulationEvent> {
+        let instruction = Instruction::try_from(line).unwrap();
+        let _ = self.system.process_instruction(&instruction);
+        self.events.borrow_mut().drain(..).collect()
+    }
+}
+
+/// Evento del inicio de la instrucción escrita como en el archivo de entrada
+fn started(line: &str) -> SimulationEvent {
+    SimulationEvent::InstructionStarted {
+        instruction: Instruction::try_from(line).unwrap(),
+    }
+}
+
+/// Eventos del final de una instrucción que tomó `time` y dejó el tiempo del sistema en `now`,
+/// ambos en milisegundos
+fn finished(time: u32, now: u32) -> Vec<SimulationEvent> {
+    vec![
+        SimulationEvent::InstructionFinished {
+            time: Time::from_miliseconds(time),
+        },
+        SimulationEvent::TimeAdvanced {
+            now: Time::from_miliseconds(now),
+        },
+    ]
+}
+
+#[test]
+fn process_loads_its_pages_in_order() {
+    let mut recorded = RecordedSystem::new();
+    let mut expected = vec![
+        started("P 32 1"),
+        SimulationEvent::ProcessCreated {
+            pid: 1,
+            size: 32,
+            pages: 2,
+            demand_paging: false,
+        },
+        SimulationEvent::PageLoaded {
+            pid: 1,
+            page: 0,
+            frame: 0,
+        },
+        SimulationEvent::PageLoaded {
+            pid: 1,
+            page: 1,
+            frame: 1,
+        },
+    ];
+    expected.extend(finished(2000, 2000));
+    assert_eq!(recorded.run("P 32 1"), expected);
+}
+
+#[test]
+fn page_fault_swaps_out_before_swapping_in() {
+    let mut recorded = RecordedSystem::new();
+    recorded.run("P 32 1");
+    // El proceso 2 saca la página 0 del proceso 1 al espacio swap
+    recorded.run("P 16 2");
+    // Para regresarla se saca la página que lleva más tiempo en la memoria real, y la página 0
+    // entra en su marco
+    let mut expected = vec![
+        started("A 0 1 1"),
+        SimulationEvent::SwapOut {
+            pid: 1,
+            page: 1,
+            frame: 1,
+            swap_frame: 0,
+            write_back: true,
+        },
+        SimulationEvent::PageFault { pid: 1, page: 0 },
+        SimulationEvent::SwapIn {
+            pid: 1,
+            page: 0,
+            swap_frame: 0,
+            frame: 1,
+        },
+        SimulationEvent::PageAccessed {
+            pid: 1,
+            address: 0,
+            page: 0,
+            frame: 1,
+            real_address: 16,
+            modifies: true,
+        },
+    ];
+    expected.extend(finished(1100, 5100));
+    assert_eq!(recorded.run("A 0 1 1"), expected);
+}
+
+#[test]
+fn free_releases_real_memory_before_swap_space() {
+    let mut recorded = RecordedSystem::new();
+    recorded.run("P 32 1");
+    recorded.run("P 16 2");
+    let mut expected = vec![
+        started("L 1"),
+        SimulationEvent::FrameFreed {
+            pid: 1,
+            page: 1,
+            frame: Frame::real(1),
+        },
+        SimulationEvent::FrameFreed {
+            pid: 1,
+            page: 0,
+            frame: Frame::swap(0),
+        },
+    ];
+    expected.extend(finished(200, 4200));
+    assert_eq!(recorded.run("L 1"), expected);
+}
+
+#[test]
+fn failed_instruction_does_not_advance_time() {
+    let mut recorded = RecordedSystem::new();
+    assert_eq!(
+        recorded.run("L 7"),
+        vec![
+            started("L 7"),
+            SimulationEvent::InstructionFailed {
+                error: SimulationError::UnknownPid(7),
+            },
+        ]
+    );
+}
@@ -0,0 +1,104 @@
+use std::convert::TryFrom;
+
+use memory_admin_simulator::{
+    FrameAllocation, Instruction, PolicyOptions, PolicyRegistry, SimulationError,
+    SwapExhaustionResponse, System, SystemOptions,
+};
+
+/// Crea un sistema con FIFO, 4 marcos de memoria real, 4 marcos de espacio swap y cuotas iguales
+fn system_with_equal_quotas(swap_exhaustion: SwapExhaustionResponse) -> System {
+    let policy = PolicyRegistry::default()
+        .create("fifo", &PolicyOptions::default())
+        .unwrap();
+    let options = SystemOptions {
+        page_size: 16,
+        real_memory_size: 64,
+        swap_space_size: 64,
+        frame_allocation: FrameAllocation::Equal,
+        swap_exhaustion,
+        ..SystemOptions::default()
+    };
+    System::new(policy, &options)
+}
+
+/// Ejecuta una instrucción escrita como en el archivo de entrada
+fn run(system: &mut System, line: &str) -> Result<(), SimulationError> {
+    let instruction = Instruction::try_from(line).unwrap();
+    system.process_instruction(&instruction).map(|_| ())
+}
+
+/// Revisa que cada página en las memorias le pertenezca a un proceso vivo y que su tabla de
+/// páginas apunte a ella
+fn assert_every_page_has_owner(system: &System) {
+    let memories = [system.get_real_memory(), system.get_swap_space()];
+    for page in memories.iter().flat_map(|memory| memory.iter().flatten()) {
+        let (pid, page_index) = page.get_page_info();
+        let process = system.get_process(pid).unwrap_or_else(|| {
+            panic!(
+                "La página {} del proceso {} no tiene dueño",
+                page_index, pid
+            )
+        });
+        assert!(process.get_page_frame(page_index).is_some());
+    }
+}
+
+#[test]
+fn rejected_process_leaves_no_pages_and_keeps_quotas() {
+    let mut system = system_with_equal_quotas(SwapExhaustionResponse::Reject);
+    run(&mut system, "P 16 1").unwrap();
+    run(&mut system, "P 16 2").unwrap();
+    // Con su cuota el proceso 3 sólo puede cargar una página a la memoria real, y las otras
+    // cinco no caben en el espacio swap
+    for _ in 0..2 {
+        match run(&mut system, "P 96 3") {
+            Err(SimulationError::OutOfMemory { requested, .. }) => assert_eq!(requested, 96),
+            other => panic!("Se esperaba OutOfMemory, se obtuvo {:?}", other),
+        }
+        assert!(system.get_process(3).is_none());
+        assert_eq!(system.get_real_memory().iter().flatten().count(), 2);
+        assert_eq!(system.get_swap_space().iter().flatten().count(), 0);
+        assert_eq!(system.get_process(1).unwrap().get_quota(), Some(2));
+        assert_eq!(system.get_process(2).unwrap().get_quota(), Some(2));
+        assert_every_page_has_owner(&system);
+    }
+    // Al liberar un proceso la cuota del proceso 3 crece y ya cabe
+    run(&mut system, "L 2").unwrap();
+    run(&mut system, "P 96 3").unwrap();
+    assert_every_page_has_owner(&system);
+}
+
+#[test]
+fn killing_for_a_process_under_quotas_leaves_no_orphan_pages() {
+    let mut system = system_with_equal_quotas(SwapExhaustionResponse::Kill);
+    run(&mut system, "P 16 1").unwrap();
+    run(&mut system, "P 16 2").unwrap();
+    run(&mut system, "P 96 3").unwrap();
+    assert!(system.get_process(3).is_some());
+    assert_every_page_has_owner(&system);
+    // Reusar el pid de un proceso terminado no debe encontrar páginas sin dueño
+    let _ = run(&mut system, "P 16 2");
+    for address in (0..96).step_by(16) {
+        run(&mut system, &format!("A {} 3 1", address)).unwrap();
+        assert_every_page_has_owner(&system);
+    }
+}
+
+#[test]
+fn loading_a_process_replaces_pages_over_quota_first() {
+    let mut system = system_with_equal_quotas(SwapExhaustionResponse::Reject);
+    run(&mut system, "P 16 1").unwrap();
+    run(&mut system, "P 48 2").unwrap();
+    // El proceso 2 regresa su última página a la memoria real, por lo que tiene 2 páginas ahí
+    run(&mut system, "A 32 2 0").unwrap();
+    run(&mut system, "P 16 3").unwrap();
+    // Con 4 procesos la cuota de cada uno es un marco: la página que sale para cargar al proceso
+    // 4 debe ser del proceso 2, que se pasa de su cuota, y no la única del proceso 1
+    run(&mut system, "P 16 4").unwrap();
+    for pid in 1..=4 {
+        let process = system.get_process(pid).unwrap();
+        assert_eq!(process.get_quota(), Some(1));
+        assert_eq!(process.count_resident_pages(), 1, "proceso {}", pid);
+    }
+    assert_every_page_has_owner(&system);
+}
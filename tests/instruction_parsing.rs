@@ -0,0 +1,37 @@
+use std::convert::TryFrom;
+
+use memory_admin_simulator::Instruction;
+
+/// Regresa la prioridad de la instrucción P escrita como en el archivo de entrada, o el mensaje
+/// de error si no se pudo parsear
+fn priority(line: &str) -> Result<u16, String> {
+    match Instruction::try_from(line) {
+        Ok(Instruction::Process { priority, .. }) => Ok(priority),
+        Ok(other) => panic!("Se esperaba una instrucción P, se obtuvo {:?}", other),
+        Err((_, message)) => Err(message),
+    }
+}
+
+#[test]
+fn priority_needs_an_explicit_token() {
+    assert_eq!(priority("P 16 1"), Ok(1));
+    assert_eq!(priority("P 16 1 prio=3"), Ok(3));
+    // Un comentario que empieza con un número no es la prioridad
+    assert_eq!(priority("P 16 1 0 bytes"), Ok(1));
+    assert_eq!(priority("P 16 1 3 procesos"), Ok(1));
+}
+
+#[test]
+fn invalid_priorities_are_parse_errors() {
+    assert!(priority("P 16 1 prio=0").is_err());
+    assert!(priority("P 16 1 prio=70000").is_err());
+    assert!(priority("P 16 1 prio=alta").is_err());
+}
+
+#[test]
+fn priority_survives_printing_the_instruction() {
+    let instruction = Instruction::try_from("P 16 1 prio=3").unwrap();
+    let printed = instruction.to_string();
+    let first_line = printed.lines().next().unwrap();
+    assert_eq!(Instruction::try_from(first_line).unwrap(), instruction);
+}
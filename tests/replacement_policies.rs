@@ -0,0 +1,224 @@
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+
+use memory_admin_simulator::algorithm::{ClockPolicy, NruPolicy};
+use memory_admin_simulator::{
+    Instruction, MissRatioCurve, PolicyOptions, PolicyRegistry, ProcessPage, ReplacementPolicy,
+    System, SystemOptions, Time,
+};
+
+/// Tamaño de página de los sistemas de las pruebas
+const PAGE_SIZE: usize = 16;
+
+/// Convierte cada línea en una instrucción, como si vinieran de un archivo de entrada
+fn parse(lines: &[String]) -> Vec<Instruction> {
+    lines
+        .iter()
+        .map(|line| Instruction::try_from(line.as_str()).unwrap())
+        .collect()
+}
+
+/// Genera las instrucciones A (sin modificar) que acceden en orden a las páginas de la cadena de
+/// referencias del proceso `pid`
+fn accesses(pid: u16, reference_string: &[usize]) -> Vec<String> {
+    reference_string
+        .iter()
+        .map(|page| format!("A {} {} 0", page * PAGE_SIZE, pid))
+        .collect()
+}
+
+/// Ejecuta las instrucciones con la política, paginación por demanda y `frames` marcos de
+/// memoria real, y regresa el número de fallos de página de cada escenario
+fn page_faults(policy: &str, frames: usize, lines: &[String]) -> Vec<usize> {
+    let policy = PolicyRegistry::default()
+        .create(policy, &PolicyOptions::default())
+        .unwrap();
+    let options = SystemOptions {
+        page_size: PAGE_SIZE,
+        real_memory_size: frames * PAGE_SIZE,
+        swap_space_size: 64 * PAGE_SIZE,
+        demand_paging: true,
+        ..SystemOptions::default()
+    };
+    System::new(policy, &options)
+        .run_scenarios(&parse(lines))
+        .iter()
+        .map(|report| report.page_faults)
+        .collect()
+}
+
+/// Cadena de referencias de los libros de texto para comparar las políticas
+const TEXTBOOK_STRING: [usize; 20] = [7, 0, 1, 2, 0, 3, 0, 4, 2, 3, 0, 3, 2, 1, 2, 0, 1, 7, 0, 1];
+
+#[test]
+fn optimal_matches_the_textbook_fault_count() {
+    let mut lines = vec!["P 128 1".to_string()];
+    lines.extend(accesses(1, &TEXTBOOK_STRING));
+    assert_eq!(page_faults("opt", 3, &lines), vec![9]);
+}
+
+#[test]
+fn optimal_keeps_trace_positions_after_a_reset() {
+    // Los dos escenarios son iguales, por lo que deben tener los mismos fallos de página
+    let mut lines = vec!["P 128 1".to_string()];
+    lines.extend(accesses(1, &TEXTBOOK_STRING));
+    lines.push("F".to_string());
+    lines.push("P 128 1".to_string());
+    lines.extend(accesses(1, &TEXTBOOK_STRING));
+    assert_eq!(page_faults("opt", 3, &lines), vec![9, 9]);
+}
+
+#[test]
+fn optimal_ignores_accesses_of_a_reused_pid() {
+    // La página 0 del proceso 1 se libera antes de que el nuevo proceso 1 acceda a su página 0,
+    // por lo que es la que se reemplaza en vez de la página 0 del proceso 2
+    let lines: Vec<String> = [
+        "P 16 1", "P 32 2", "A 0 1 0", "A 0 2 0", "A 16 2 0", "L 1", "P 16 1", "A 0 1 0", "A 0 2 0",
+    ]
+    .iter()
+    .map(|line| line.to_string())
+    .collect();
+    assert_eq!(page_faults("opt", 2, &lines), vec![4]);
+}
+
+#[test]
+fn fifo_shows_beladys_anomaly() {
+    // Con la cadena clásica de la anomalía FIFO tiene más fallos con 4 marcos que con 3, porque
+    // una página que regresa del espacio swap se forma al final de la cola
+    let mut lines = vec!["P 80 1".to_string()];
+    lines.extend(accesses(1, &[0, 1, 2, 3, 0, 1, 4, 0, 1, 2, 3, 4]));
+    assert_eq!(page_faults("fifo", 3, &lines), vec![9]);
+    assert_eq!(page_faults("fifo", 4, &lines), vec![10]);
+}
+
+/// Genera una lista de instrucciones pseudoaleatoria (siempre la misma) con 3 pids que se
+/// liberan y se vuelven a usar con otro tamaño, y con dos escenarios
+fn trace_with_reused_pids() -> Vec<String> {
+    let mut seed: u32 = 17;
+    let mut next = |bound: u32| {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        (seed >> 16) % bound
+    };
+    let mut lines = Vec::new();
+    let mut sizes = [0; 3];
+    for step in 0..300 {
+        if step == 150 {
+            lines.push("F".to_string());
+            sizes = [0; 3];
+        }
+        let pid = next(3) as usize;
+        if sizes[pid] == 0 {
+            sizes[pid] = (next(5) as usize + 1) * PAGE_SIZE;
+            lines.push(format!("P {} {}", sizes[pid], pid + 1));
+        } else if next(20) == 0 {
+            sizes[pid] = 0;
+            lines.push(format!("L {}", pid + 1));
+        } else {
+            let address = next(sizes[pid] as u32);
+            lines.push(format!("A {} {} {}", address, pid + 1, next(2)));
+        }
+    }
+    lines
+}
+
+#[test]
+fn miss_ratio_curve_matches_lru() {
+    let lines = trace_with_reused_pids();
+    assert!(lines.iter().filter(|line| line.starts_with('L')).count() > 1);
+    let curve = MissRatioCurve::from_trace(&parse(&lines), PAGE_SIZE);
+    for frames in 1..=curve.max_frames() + 1 {
+        let faults: usize = page_faults("lru", frames, &lines).iter().sum();
+        assert_eq!(curve.misses(frames), faults, "con {} marcos", frames);
+    }
+}
+
+/// Crea una memoria real llena con una página en cada marco, con el bit de referencia prendido
+/// en los marcos de `referenced`
+fn full_memory(frames: usize, referenced: &[usize]) -> Vec<Option<ProcessPage>> {
+    (0..frames)
+        .map(|frame| {
+            let mut page = ProcessPage::new(1, frame, Time::new());
+            page.set_referenced(referenced.contains(&frame));
+            Some(page)
+        })
+        .collect()
+}
+
+#[test]
+fn clock_gives_referenced_pages_a_second_chance() {
+    let mut real_memory = full_memory(4, &[0, 2]);
+    let mut clock = ClockPolicy::new();
+    // La manecilla apaga el bit del marco 0 y reemplaza el marco 1
+    assert_eq!(clock.find_page_to_replace(&mut real_memory), 1);
+    assert!(!real_memory[0].as_ref().unwrap().is_referenced());
+    // Después apaga el bit del marco 2 y reemplaza el marco 3
+    assert_eq!(clock.find_page_to_replace(&mut real_memory), 3);
+    assert!(!real_memory[2].as_ref().unwrap().is_referenced());
+    // En la siguiente vuelta el marco 0 ya no tiene segunda oportunidad
+    assert_eq!(clock.find_page_to_replace(&mut real_memory), 0);
+}
+
+#[test]
+fn clock_only_clears_the_bits_of_candidates() {
+    let mut real_memory = full_memory(4, &[0, 1, 2, 3]);
+    let mut clock = ClockPolicy::new();
+    let candidates: BTreeSet<usize> = [2, 3].iter().copied().collect();
+    // Se apagan los bits de los candidatos en la primera vuelta y en la segunda se reemplaza el
+    // primero de ellos, sin tocar los bits de los demás marcos
+    assert_eq!(
+        clock.find_page_to_replace_among(&mut real_memory, &candidates),
+        2
+    );
+    assert!(real_memory[0].as_ref().unwrap().is_referenced());
+    assert!(real_memory[1].as_ref().unwrap().is_referenced());
+    assert!(!real_memory[3].as_ref().unwrap().is_referenced());
+}
+
+/// Crea una memoria real llena en la que la página de cada marco tiene los bits (referencia,
+/// modificación) proporcionados
+fn memory_with_bits(bits: &[(bool, bool)]) -> Vec<Option<ProcessPage>> {
+    bits.iter()
+        .enumerate()
+        .map(|(frame, &(referenced, dirty))| {
+            let mut page = ProcessPage::new(1, frame, Time::new());
+            page.set_referenced(referenced);
+            page.set_dirty(dirty);
+            Some(page)
+        })
+        .collect()
+}
+
+#[test]
+fn nru_replaces_the_lowest_class_first() {
+    // Un marco de cada clase, de la clase 3 a la clase 0
+    let mut real_memory =
+        memory_with_bits(&[(true, true), (true, false), (false, true), (false, false)]);
+    let mut nru = NruPolicy::new(Time::from_miliseconds(5000));
+    let mut victims = Vec::new();
+    for _ in 0..4 {
+        let frame = nru.find_page_to_replace(&mut real_memory);
+        victims.push(frame);
+        // La página que entra queda referenciada y modificada, en la clase más alta
+        let page = real_memory[frame].as_mut().unwrap();
+        page.set_referenced(true);
+        page.set_dirty(true);
+    }
+    // Las clases 0, 1 y 2, y después, con todos los marcos en la clase 3, el primero a partir de
+    // la manecilla, que quedó en el marco siguiente al último reemplazado
+    assert_eq!(victims, vec![3, 2, 1, 2]);
+}
+
+#[test]
+fn nru_clears_reference_bits_every_tick() {
+    let mut real_memory = memory_with_bits(&[(true, true), (true, false)]);
+    let mut nru = NruPolicy::new(Time::from_miliseconds(5000));
+    // Antes de que pase el intervalo los bits se quedan como estaban
+    nru.on_time_advanced(Time::from_miliseconds(4900), &mut real_memory);
+    assert!(real_memory.iter().flatten().all(ProcessPage::is_referenced));
+    // Al pasar el intervalo se apagan los bits de referencia, pero no los de modificación,
+    // por lo que la página limpia queda en la clase 0
+    nru.on_time_advanced(Time::from_miliseconds(5000), &mut real_memory);
+    assert!(!real_memory.iter().flatten().any(ProcessPage::is_referenced));
+    assert!(real_memory[0].as_ref().unwrap().is_dirty());
+    assert_eq!(nru.find_page_to_replace(&mut real_memory), 1);
+}
@@ -0,0 +1,65 @@
+use std::convert::TryFrom;
+
+use memory_admin_simulator::{
+    Instruction, PolicyOptions, PolicyRegistry, Report, System, SystemOptions,
+};
+
+/// Ejecuta las instrucciones con FIFO, 2 marcos de memoria real y 4 marcos de espacio swap, y
+/// regresa el reporte del escenario
+fn report(lines: &[&str]) -> Report {
+    let policy = PolicyRegistry::default()
+        .create("fifo", &PolicyOptions::default())
+        .unwrap();
+    let options = SystemOptions {
+        page_size: 16,
+        real_memory_size: 32,
+        swap_space_size: 64,
+        ..SystemOptions::default()
+    };
+    let trace: Vec<Instruction> = lines
+        .iter()
+        .map(|line| Instruction::try_from(*line).unwrap())
+        .collect();
+    System::new(policy, &options)
+        .run_scenarios(&trace)
+        .remove(0)
+}
+
+#[test]
+fn faults_on_pages_loaded_by_p_are_capacity_faults() {
+    // La página 0 del proceso 1 la cargó la instrucción P y el proceso 2 la sacó al espacio
+    // swap, por lo que regresarla es un fallo de capacidad aunque sea su primer acceso
+    let report = report(&["P 32 1", "P 16 2", "A 0 1 0", "F"]);
+    let process = &report.processes[0];
+    assert_eq!(process.pid, 1);
+    assert_eq!((process.compulsory_faults, process.capacity_faults), (0, 1));
+    assert_eq!(process.fault_rate, Some(1.0));
+}
+
+#[test]
+fn csv_leaves_fault_rates_without_accesses_empty() {
+    let report = report(&["P 16 1", "P 16 2", "A 0 2 0", "F"]);
+    assert_eq!(report.processes[0].fault_rate, None);
+    let mut csv = Vec::new();
+    report.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let rows: Vec<&str> = csv.lines().collect();
+    // El proceso 1 no tuvo accesos, y el promedio sólo toma en cuenta al proceso 2
+    assert!(rows[1].starts_with("1,1,"));
+    assert!(rows[1].ends_with(",0,0,0,0,"));
+    assert!(rows[3].starts_with("1,average,"));
+    assert!(rows[3].ends_with(",0"));
+    assert_eq!(rows[4], "1,system,,,,,,,,,,,,1,0,0,0,0");
+    assert!(!csv.contains("NaN"));
+}
+
+#[test]
+fn csv_leaves_averages_without_processes_empty() {
+    let report = report(&["F"]);
+    assert_eq!(report.average_turnaround, None);
+    let mut csv = Vec::new();
+    report.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert!(!csv.contains("NaN"));
+    assert_eq!(csv.lines().nth(1), Some("1,average,,,,,,,,,,,,,,,,"));
+}